@@ -0,0 +1,539 @@
+//! The alpha-beta search of the best move of a [`game::Game`], for the grids too big for [`minimax`](super::minimax).
+//!
+//! It is the same search as the minimax, but the moves that can not change the choice of the players are not
+//! searched. To search as few moves as possible:
+//! - the search is made deeper and deeper (iterative deepening), until the [`Budget`] is spent, and the best move of
+//!   the previous depth is searched first
+//! - the positions already searched are saved in a transposition table, keyed by [`game::Game::position_hash`]
+//! - the moves are ordered: the best move saved in the table first, then the moves close to the center
+//! - on the grids of more than [`NEIGHBOURHOOD_MIN_CELLS`] cells, only the free cells near a filled one are searched
+//!
+//! When the search is stopped before the end of the game, the positions are evaluated by counting the lines still
+//! winnable by each player.
+//!
+//! # Example
+//!
+//! ```rust
+//! let game = game::Game::new(9, 9, 5);
+//! let mut search = alpha_beta::Search::new(alpha_beta::Budget::Time(Duration::from_secs(1)));
+//!
+//! let best_move = search.best_move(&game);
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::{game, DEBUG, INFO, TRACE};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The score of a win at the root of the search, a win `n` moves later is worth `WIN_SCORE - n`
+pub const WIN_SCORE: i32 = 1_000_000;
+
+/// From this number of cells, only the free cells near a filled one are searched
+pub const NEIGHBOURHOOD_MIN_CELLS: usize = 25;
+
+/// The resources a [`Search`] can spend to choose a move
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Budget {
+    /// The search stops after the given duration
+    Time(Duration),
+    /// The search stops after the given number of positions searched
+    Nodes(u64),
+}
+
+/// Read a budget: a duration in seconds (`2s`) or milliseconds (`500ms`), or a number of positions (`100000`).
+impl FromStr for Budget {
+    type Err = String;
+
+    fn from_str(p_text: &str) -> Result<Self, Self::Err> {
+        let l_text = p_text.trim().to_lowercase();
+        let l_error = || format!("`{}` is not a duration or a number of positions", p_text.trim());
+
+        if let Some(l_milliseconds) = l_text.strip_suffix("ms") {
+            l_milliseconds
+                .trim()
+                .parse::<u64>()
+                .map(|l_value| Budget::Time(Duration::from_millis(l_value)))
+                .map_err(|_| l_error())
+        } else if let Some(l_seconds) = l_text.strip_suffix('s') {
+            l_seconds
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|l_value| l_value.is_finite() && *l_value > 0.0)
+                .map(|l_value| Budget::Time(Duration::from_secs_f64(l_value)))
+                .ok_or_else(l_error)
+        } else {
+            l_text.parse::<u64>().map(Budget::Nodes).map_err(|_| l_error())
+        }
+    }
+}
+
+/// An alpha-beta search, with its transposition table.
+pub struct Search {
+    /// The resources to spend for each move
+    budget: Budget,
+    /// The maximal depth searched, `None` to search until the end of the game if the budget allows it
+    max_depth: Option<usize>,
+    /// The positions already searched
+    table: HashMap<u64, Entry>,
+    /// The number of positions searched for the current move
+    nodes: u64,
+    /// When the search of the current move started
+    start: Instant,
+    /// Set when the budget is spent, the search in progress is then abandoned
+    stopped: bool,
+    /// If set, only the free cells near a filled one are searched on the big grids, see [`NEIGHBOURHOOD_MIN_CELLS`]
+    prune: bool,
+}
+
+/// The result of the search of a position, see [`Search::analyse`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Analysis {
+    /// The coordinates `(row, column)` of the best move found
+    best_move: (usize, usize),
+    /// The score of the position for the current player
+    score: i32,
+    /// The number of moves searched ahead
+    depth: usize,
+    /// Set if the score is the exact value of the position: the end of the game has been reached by the search
+    solved: bool,
+}
+
+impl Analysis {
+    /// Return the coordinates `(row, column)` of the best move found
+    pub fn best_move(&self) -> (usize, usize) {
+        self.best_move
+    }
+
+    /// Return the score of the position for the current player: above `WIN_SCORE - <number of cells>` it is a win,
+    /// below its opposite a loss
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    /// Return the number of moves searched ahead
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Return `true` if the score is the exact value of the position, `false` if it is an evaluation
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+}
+
+impl Search {
+    /// Create a search spending the given budget for each move.
+    pub fn new(p_budget: Budget) -> Self {
+        Search {
+            budget: p_budget,
+            max_depth: None,
+            table: HashMap::new(),
+            nodes: 0,
+            start: Instant::now(),
+            stopped: false,
+            prune: true,
+        }
+    }
+
+    /// Search every free cell, even on the big grids, so that the wins and losses found are certain.
+    pub fn without_pruning(mut self) -> Self {
+        self.prune = false;
+        self
+    }
+
+    /// Limit the search to the given number of moves.
+    pub fn with_max_depth(mut self, p_max_depth: usize) -> Self {
+        self.max_depth = Some(p_max_depth);
+        self
+    }
+
+    /// Return the coordinates `(row, column)` of the best move found for the current player of the game and its
+    /// score, or `None` if the game is over, see [`Search::analyse`].
+    pub fn best_move(&mut self, p_game: &game::Game) -> Option<((usize, usize), i32)> {
+        self.analyse(p_game).map(|l_analysis| (l_analysis.best_move, l_analysis.score))
+    }
+
+    /// Search the position, and return the best move found for the current player with its score, or `None` if the
+    /// game is over.
+    ///
+    /// The score is for the current player: above `WIN_SCORE - <number of cells>` it is a certain win, below its
+    /// opposite a certain loss, unless the free cells far from the filled ones are not searched, see
+    /// [`Search::without_pruning`].
+    pub fn analyse(&mut self, p_game: &game::Game) -> Option<Analysis> {
+        if p_game.is_over() {
+            return None;
+        }
+
+        let mut l_game = p_game.clone();
+        let l_free_cells = p_game.free_cells().len();
+        let l_max_depth = self.max_depth.unwrap_or(l_free_cells).min(l_free_cells);
+        let mut l_best: Option<Analysis> = None;
+
+        self.nodes = 0;
+        self.start = Instant::now();
+        self.stopped = false;
+
+        for l_depth in 1..=l_max_depth {
+            let l_score = self.alpha_beta(&mut l_game, l_depth, 0, -WIN_SCORE - 1, WIN_SCORE + 1);
+            if self.stopped {
+                break;
+            }
+
+            let l_end_found = l_score.abs() > WIN_SCORE - (l_free_cells as i32) - 1;
+            l_best = self
+                .table
+                .get(&l_game.position_hash())
+                .and_then(|l_entry| l_entry.best_move)
+                .map(|l_move| Analysis {
+                    best_move: l_move,
+                    score: l_score,
+                    depth: l_depth,
+                    solved: l_end_found || l_depth == l_free_cells,
+                });
+
+            INFO!(
+                "[AlphaBeta] Depth {} : best move {:?}, {} positions searched",
+                l_depth,
+                l_best,
+                self.nodes
+            );
+
+            if l_end_found {
+                break; // The end of the game is found
+            }
+        }
+
+        // The budget was too small to finish even the first depth, play at least a move
+        l_best.or_else(|| {
+            order_moves(p_game, None, self.prune)
+                .first()
+                .map(|l_move| Analysis {
+                    best_move: *l_move,
+                    score: 0,
+                    depth: 0,
+                    solved: false,
+                })
+        })
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The kind of score saved in the transposition table
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Bound {
+    /// The score is exact
+    Exact,
+    /// The real score is at least the saved one
+    Lower,
+    /// The real score is at most the saved one
+    Upper,
+}
+
+/// A position saved in the transposition table
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    /// The depth searched from this position
+    depth: usize,
+    /// The score found, see [`Bound`]
+    score: i32,
+    /// The kind of the score
+    bound: Bound,
+    /// The best move found from this position
+    best_move: Option<(usize, usize)>,
+}
+
+/// The number of positions searched between two checks of the time spent
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+impl Search {
+    /// Return the score of the position for the current player, searching `p_depth` moves, `p_ply` moves after the
+    /// root of the search.
+    fn alpha_beta(
+        &mut self,
+        p_game: &mut game::Game,
+        p_depth: usize,
+        p_ply: usize,
+        mut p_alpha: i32,
+        p_beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.budget_spent() {
+            self.stopped = true;
+            return 0;
+        }
+
+        match p_game.outcome() {
+            game::GameOutcome::Won(l_winner) => {
+                let l_score = WIN_SCORE - p_ply as i32;
+                return if l_winner == p_game.current_player() {
+                    l_score
+                } else {
+                    -l_score
+                };
+            }
+            game::GameOutcome::Draw => return 0,
+            game::GameOutcome::InProgress => {}
+        }
+
+        if p_depth == 0 {
+            return evaluate(p_game);
+        }
+
+        let l_hash = p_game.position_hash();
+        let l_alpha_origin = p_alpha;
+        let mut l_table_move = None;
+        if let Some(l_entry) = self.table.get(&l_hash) {
+            l_table_move = l_entry.best_move;
+            if l_entry.depth >= p_depth {
+                let l_score = from_table_score(l_entry.score, p_ply);
+                match l_entry.bound {
+                    Bound::Exact => return l_score,
+                    Bound::Lower if l_score >= p_beta => return l_score,
+                    Bound::Upper if l_score <= p_alpha => return l_score,
+                    _ => {}
+                }
+            }
+        }
+
+        let mut l_best_score = -WIN_SCORE - 1;
+        let mut l_best_move = None;
+        for (l_x, l_y) in order_moves(p_game, l_table_move, self.prune) {
+            p_game.play(l_x, l_y).expect("[AI] A free cell can always be filled");
+            let l_score = -self.alpha_beta(p_game, p_depth - 1, p_ply + 1, -p_beta, -p_alpha);
+            p_game.undo();
+
+            if self.stopped {
+                return 0;
+            }
+
+            if l_score > l_best_score {
+                l_best_score = l_score;
+                l_best_move = Some((l_x, l_y));
+            }
+            p_alpha = p_alpha.max(l_score);
+            if p_alpha >= p_beta {
+                DEBUG!("[AlphaBeta] Cut-off at ply {} after ({}, {})", p_ply, l_x, l_y);
+                break;
+            }
+        }
+
+        let l_bound = if l_best_score <= l_alpha_origin {
+            Bound::Upper
+        } else if l_best_score >= p_beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(
+            l_hash,
+            Entry {
+                depth: p_depth,
+                score: to_table_score(l_best_score, p_ply),
+                bound: l_bound,
+                best_move: l_best_move,
+            },
+        );
+
+        l_best_score
+    }
+
+    /// Test if the budget of the current move is spent.
+    fn budget_spent(&self) -> bool {
+        match self.budget {
+            Budget::Nodes(l_max_nodes) => self.nodes > l_max_nodes,
+            Budget::Time(l_duration) => {
+                self.nodes.is_multiple_of(TIME_CHECK_INTERVAL) && self.start.elapsed() >= l_duration
+            }
+        }
+    }
+}
+
+/// Convert a score relative to the root into a score relative to the position, to be saved in the table.
+///
+/// A win is saved as the number of moves from the position, not from the root, as the same position can be reached
+/// at different depths.
+fn to_table_score(p_score: i32, p_ply: usize) -> i32 {
+    if p_score > WIN_SCORE / 2 {
+        p_score + p_ply as i32
+    } else if p_score < -WIN_SCORE / 2 {
+        p_score - p_ply as i32
+    } else {
+        p_score
+    }
+}
+
+/// Convert a score read in the table into a score relative to the root, see [`to_table_score`].
+fn from_table_score(p_score: i32, p_ply: usize) -> i32 {
+    if p_score > WIN_SCORE / 2 {
+        p_score - p_ply as i32
+    } else if p_score < -WIN_SCORE / 2 {
+        p_score + p_ply as i32
+    } else {
+        p_score
+    }
+}
+
+/// Return the moves to search, the move of the table first, then from the center of the grid to its borders.
+///
+/// With `p_prune`, on the grids of at least [`NEIGHBOURHOOD_MIN_CELLS`] cells, only the cells at most two cells away
+/// from a filled one are kept, if any. With the gravity there is at most one move per column, they are all kept.
+fn order_moves(
+    p_game: &game::Game,
+    p_first: Option<(usize, usize)>,
+    p_prune: bool,
+) -> Vec<(usize, usize)> {
+    let mut l_moves = p_game.legal_moves();
+
+    if p_prune && !p_game.has_gravity() && p_game.rows() * p_game.columns() >= NEIGHBOURHOOD_MIN_CELLS {
+        let l_near: Vec<(usize, usize)> = l_moves
+            .iter()
+            .copied()
+            .filter(|(l_x, l_y)| has_filled_neighbour(p_game, *l_x, *l_y, 2))
+            .collect();
+        if !l_near.is_empty() {
+            l_moves = l_near;
+        }
+    }
+
+    let l_center = (
+        (p_game.rows() as isize - 1) * 10 / 2,
+        (p_game.columns() as isize - 1) * 10 / 2,
+    );
+    l_moves.sort_by_key(|(l_x, l_y)| {
+        let l_distance = (*l_x as isize * 10 - l_center.0).abs() + (*l_y as isize * 10 - l_center.1).abs();
+        (Some((*l_x, *l_y)) != p_first, l_distance)
+    });
+
+    l_moves
+}
+
+/// Test if a cell at most `p_distance` cells away from the given one is filled.
+fn has_filled_neighbour(p_game: &game::Game, p_x: usize, p_y: usize, p_distance: usize) -> bool {
+    let l_rows = p_x.saturating_sub(p_distance)..(p_x + p_distance + 1).min(p_game.rows());
+    l_rows.into_iter().any(|i| {
+        (p_y.saturating_sub(p_distance)..(p_y + p_distance + 1).min(p_game.columns()))
+            .any(|j| p_game.cell(i, j) != game::Cell::Empty)
+    })
+}
+
+/// Evaluate a position not finished for the current player.
+///
+/// Every segment of the grid long enough to win, and where only one player has symbols, is worth for this player
+/// `4^<number of symbols>`. With the misère rules, these segments are the ones to avoid and the score is the opposite.
+fn evaluate(p_game: &game::Game) -> i32 {
+    let l_player = p_game.current_player();
+    let l_length = p_game.win_length() as isize;
+    let mut l_score: i32 = 0;
+
+    for i in 0..p_game.rows() as isize {
+        for j in 0..p_game.columns() as isize {
+            for (l_dx, l_dy) in [(0, 1), (1, 0), (1, 1), (1, -1)].iter() {
+                let (l_end_x, l_end_y) = (i + l_dx * (l_length - 1), j + l_dy * (l_length - 1));
+                if l_end_x < 0
+                    || l_end_y < 0
+                    || l_end_x >= p_game.rows() as isize
+                    || l_end_y >= p_game.columns() as isize
+                {
+                    continue;
+                }
+
+                let (mut l_mine, mut l_theirs) = (0, 0);
+                for l_step in 0..l_length {
+                    match p_game.cell((i + l_dx * l_step) as usize, (j + l_dy * l_step) as usize) {
+                        game::Cell::Occupied(l_owner) if l_owner == l_player => l_mine += 1,
+                        game::Cell::Occupied(_) => l_theirs += 1,
+                        game::Cell::Empty => {}
+                    }
+                }
+
+                if l_theirs == 0 && l_mine > 0 {
+                    l_score += 4_i32.pow(l_mine);
+                } else if l_mine == 0 && l_theirs > 0 {
+                    l_score -= 4_i32.pow(l_theirs);
+                }
+            }
+        }
+    }
+
+    if p_game.is_misere() {
+        l_score = -l_score;
+    }
+
+    l_score.clamp(-WIN_SCORE / 4, WIN_SCORE / 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The budget of the searches expected to reach the end of the game
+    const LARGE_BUDGET: Budget = Budget::Nodes(1_000_000);
+
+    /// Return the best move found and its score for the position written in the notation of the [game] module
+    fn search(p_search: &mut Search, p_notation: &str) -> Option<((usize, usize), i32)> {
+        p_search.best_move(&game::Game::from_notation(p_notation).unwrap())
+    }
+
+    #[test]
+    fn wins_and_blocks_with_four() {
+        let (l_move, l_score) = search(
+            &mut Search::new(LARGE_BUDGET),
+            "4x4 4 XXX./OOO./..../.... X",
+        )
+        .unwrap();
+        assert_eq!(l_move, (0, 3));
+        assert_eq!(l_score, WIN_SCORE - 1);
+
+        let (l_move, _) = search(
+            &mut Search::new(Budget::Nodes(20_000)),
+            "5x5 4 ...../OXXX./.O.../...../..... O",
+        )
+        .unwrap();
+        assert_eq!(l_move, (1, 4));
+    }
+
+    #[test]
+    fn nodes_budget() {
+        let mut l_search = Search::new(Budget::Nodes(1_000));
+        let l_analysis = l_search.analyse(&game::Game::new(5, 5, 4)).unwrap();
+
+        assert!(l_search.nodes <= 1_001);
+        assert!(!l_analysis.is_solved());
+    }
+
+    #[test]
+    fn warm_table_same_answer() {
+        let mut l_warm = Search::new(LARGE_BUDGET);
+        assert_eq!(
+            search(&mut l_warm, "3x3 3 .../.X./.O. X"),
+            Some(((0, 0), WIN_SCORE - 5))
+        );
+
+        // The wins saved in the table during the first search are found again from later positions
+        for l_notation in &[
+            "3x3 3 X../.X./.O. O",
+            "3x3 3 X../.X./.OO X",
+            "3x3 3 .X./.X./.O. O",
+        ] {
+            assert_eq!(
+                search(&mut l_warm, l_notation),
+                search(&mut Search::new(LARGE_BUDGET), l_notation)
+            );
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! The hints given to a human player: the best move for the current player, with a short explanation.
+//!
+//! The obvious moves are looked for first: a move winning immediately, then a move blocking a win of the opponent.
+//! Otherwise the move is the one chosen by a perfect computer, and the explanation says what it threatens.
+//!
+//! With the misère rules, no move wins immediately: the move is the one chosen by the computer, and the explanation
+//! says if it avoids completing a line of the player.
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::ai;
+use crate::{common, game};
+use std::fmt;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Why a move is advised
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Reason {
+    /// The move wins the game
+    Win,
+    /// The opponent would win by playing this move
+    Block,
+    /// The move creates at least two threats, the opponent can not block all of them
+    Fork,
+    /// The move prevents the opponent from creating a fork
+    BlockFork,
+    /// The move creates a threat, the opponent will have to block it
+    Threat,
+    /// The move does not complete a line of the player, unlike some other ones, with the misère rules
+    Avoid,
+    /// Every move completes a line of the player, with the misère rules
+    Forced,
+    /// The move is the best one found by the search
+    Best,
+}
+
+/// Implementation of the [`fmt::Display`] trait for a [`Reason`], to explain the hint to the players
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Win => write!(f, "it is a winning move"),
+            Reason::Block => write!(f, "it blocks an immediate threat of your opponent"),
+            Reason::Fork => write!(
+                f,
+                "it creates a fork, two threats your opponent can not both block"
+            ),
+            Reason::BlockFork => write!(f, "it prevents your opponent from creating a fork"),
+            Reason::Threat => write!(f, "it creates a threat your opponent has to block"),
+            Reason::Avoid => write!(f, "it does not complete one of your lines"),
+            Reason::Forced => write!(f, "every move completes one of your lines"),
+            Reason::Best => write!(f, "it is the best move found by the computer"),
+        }
+    }
+}
+
+/// A move advised to the current player
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hint {
+    /// The coordinates `(row, column)` of the cell to fill
+    cell: (usize, usize),
+    /// Why this cell
+    reason: Reason,
+}
+
+impl Hint {
+    /// Return the coordinates `(row, column)` of the cell to fill
+    pub fn cell(&self) -> (usize, usize) {
+        self.cell
+    }
+
+    /// Return why this cell is advised
+    pub fn reason(&self) -> Reason {
+        self.reason
+    }
+}
+
+/// Return the move advised to the current player of the game, or `None` if the game is over.
+pub fn hint(p_game: &game::Game) -> Option<Hint> {
+    if p_game.is_over() {
+        return None;
+    }
+
+    if p_game.is_misere() {
+        return misere_hint(p_game);
+    }
+
+    let l_player = p_game.current_player();
+    let l_opponent = l_player.opponent();
+
+    if let Some(l_cell) = winning_cells(p_game, l_player).first() {
+        return Some(Hint {
+            cell: *l_cell,
+            reason: Reason::Win,
+        });
+    }
+    if let Some(l_cell) = winning_cells(p_game, l_opponent).first() {
+        return Some(Hint {
+            cell: *l_cell,
+            reason: Reason::Block,
+        });
+    }
+
+    let l_cell = ai::best_move(p_game, &ai::Settings::default())?;
+
+    let mut l_after = p_game.clone();
+    l_after.play(l_cell.0, l_cell.1).ok()?;
+    let l_threats = winning_cells(&l_after, l_player).len();
+
+    let l_reason = if l_threats >= 2 {
+        Reason::Fork
+    } else if fork_cells(p_game, l_opponent).contains(&l_cell) {
+        Reason::BlockFork
+    } else if l_threats == 1 {
+        Reason::Threat
+    } else {
+        Reason::Best
+    };
+
+    Some(Hint {
+        cell: l_cell,
+        reason: l_reason,
+    })
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Return the free cells where the given player would win immediately.
+fn winning_cells(p_game: &game::Game, p_player: common::Player) -> Vec<(usize, usize)> {
+    p_game
+        .legal_moves()
+        .into_iter()
+        .filter(|(l_x, l_y)| {
+            let mut l_game = as_player(p_game, p_player);
+            l_game.play(*l_x, *l_y).is_ok() && l_game.outcome() == game::GameOutcome::Won(p_player)
+        })
+        .collect()
+}
+
+/// Return the move advised to the current player of a game played with the misère rules.
+fn misere_hint(p_game: &game::Game) -> Option<Hint> {
+    let l_cell = ai::best_move(p_game, &ai::Settings::default())?;
+    let l_losing_cells = losing_cells(p_game, p_game.current_player()).len();
+
+    let l_reason = if l_losing_cells == p_game.legal_moves().len() {
+        Reason::Forced
+    } else if l_losing_cells > 0 {
+        Reason::Avoid
+    } else {
+        Reason::Best
+    };
+
+    Some(Hint {
+        cell: l_cell,
+        reason: l_reason,
+    })
+}
+
+/// Return the free cells where the given player would complete a line, and lose with the misère rules.
+fn losing_cells(p_game: &game::Game, p_player: common::Player) -> Vec<(usize, usize)> {
+    p_game
+        .legal_moves()
+        .into_iter()
+        .filter(|(l_x, l_y)| {
+            let mut l_game = as_player(p_game, p_player);
+            l_game.play(*l_x, *l_y).is_ok()
+                && l_game.outcome() == game::GameOutcome::Won(p_player.opponent())
+        })
+        .collect()
+}
+
+/// Return the free cells where the given player would create at least two immediate threats.
+fn fork_cells(p_game: &game::Game, p_player: common::Player) -> Vec<(usize, usize)> {
+    p_game
+        .legal_moves()
+        .into_iter()
+        .filter(|(l_x, l_y)| {
+            let mut l_game = as_player(p_game, p_player);
+            l_game.play(*l_x, *l_y).is_ok() && winning_cells(&l_game, p_player).len() >= 2
+        })
+        .collect()
+}
+
+/// Return a copy of the game where the given player is the current player.
+fn as_player(p_game: &game::Game, p_player: common::Player) -> game::Game {
+    let mut l_game = p_game.clone();
+    if l_game.current_player() != p_player {
+        l_game.toggle_player();
+    }
+
+    l_game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return the hint for the position written in the notation of the [game] module
+    fn hint_for(p_notation: &str) -> Option<Hint> {
+        hint(&game::Game::from_notation(p_notation).unwrap())
+    }
+
+    /// Return the hint made of the given cell and reason
+    fn advice(p_cell: (usize, usize), p_reason: Reason) -> Option<Hint> {
+        Some(Hint {
+            cell: p_cell,
+            reason: p_reason,
+        })
+    }
+
+    #[test]
+    fn immediate_moves() {
+        assert_eq!(hint_for("3x3 3 XX./OO./... X"), advice((0, 2), Reason::Win));
+        assert_eq!(
+            hint_for("3x3 3 X../OO./..X X"),
+            advice((1, 2), Reason::Block)
+        );
+    }
+
+    #[test]
+    fn forks_and_threats() {
+        assert_eq!(
+            hint_for("3x3 3 XO./.X./..O X"),
+            advice((1, 0), Reason::Fork)
+        );
+        assert_eq!(
+            hint_for("3x3 3 X../..X/O.. O"),
+            advice((2, 2), Reason::BlockFork)
+        );
+        assert_eq!(
+            hint_for("3x3 3 XO./.../... X"),
+            advice((1, 0), Reason::Threat)
+        );
+    }
+
+    #[test]
+    fn best_move_without_tactics() {
+        assert_eq!(
+            hint_for("3x3 3 .../.../... X"),
+            advice((0, 0), Reason::Best)
+        );
+    }
+
+    #[test]
+    fn no_hint_when_over() {
+        assert_eq!(hint_for("3x3 3 XXX/OO./... O"), None);
+    }
+
+    #[test]
+    fn misere_hints() {
+        assert_eq!(
+            hint_for("3x3 3 XX./OO./... X misere"),
+            advice((2, 0), Reason::Avoid)
+        );
+        assert_eq!(
+            hint_for("3x3 3 XOX/OO./X.X O misere"),
+            advice((1, 2), Reason::Forced)
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! The Monte Carlo tree search of the best move of a [`game::Game`].
+//!
+//! No knowledge of the game is needed: from the current position, a tree of the moves is grown one position at each
+//! iteration. An iteration:
+//! 1. selects a path in the tree, choosing at each position the move with the best _UCT_ value, a balance between the
+//!    moves that won the most and the moves tried the least
+//! 2. adds to the tree a move not tried yet at the end of this path
+//! 3. finishes the game with random moves, on a clone of the game (playout)
+//! 4. gives the result of the playout to every position of the path
+//!
+//! The move chosen is the one tried the most from the current position.
+//!
+//! # Example
+//!
+//! ```rust
+//! let game = game::Game::new(9, 9, 5);
+//! let mut search = mcts::Search::new(10_000, random::Random::new(42));
+//!
+//! let best_move = search.best_move(&game);
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::ai::random::Random;
+use crate::{common, game, INFO, TRACE};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A Monte Carlo tree search
+pub struct Search {
+    /// The number of iterations for each move
+    iterations: u32,
+    /// The generator of the random moves of the playouts
+    random: Random,
+}
+
+impl Search {
+    /// Create a search making the given number of iterations for each move, with the given random generator.
+    pub fn new(p_iterations: u32, p_random: Random) -> Self {
+        Search {
+            iterations: p_iterations,
+            random: p_random,
+        }
+    }
+
+    /// Return the coordinates `(row, column)` of the move tried the most for the current player of the game, or `None`
+    /// if the game is over.
+    pub fn best_move(&mut self, p_game: &game::Game) -> Option<(usize, usize)> {
+        if p_game.is_over() {
+            return None;
+        }
+
+        let mut l_tree: Vec<Node> = vec![Node::new(None, None, p_game)];
+
+        for _ in 0..self.iterations.max(1) {
+            let mut l_game = p_game.clone();
+
+            // 1. Selection
+            let mut l_node = 0;
+            while l_tree[l_node].untried.is_empty() && !l_tree[l_node].children.is_empty() {
+                l_node = select_child(&l_tree, l_node);
+                let (l_x, l_y) = l_tree[l_node]
+                    .move_played
+                    .expect("[MCTS] A child has a move");
+                l_game
+                    .play(l_x, l_y)
+                    .expect("[MCTS] A move of the tree can always be played");
+            }
+
+            // 2. Expansion
+            if !l_tree[l_node].untried.is_empty() {
+                let l_index = self.random.below(l_tree[l_node].untried.len());
+                let (l_x, l_y) = l_tree[l_node].untried.swap_remove(l_index);
+                l_game
+                    .play(l_x, l_y)
+                    .expect("[MCTS] A free cell can always be filled");
+
+                l_tree.push(Node::new(Some((l_x, l_y)), Some(l_node), &l_game));
+                let l_child = l_tree.len() - 1;
+                l_tree[l_node].children.push(l_child);
+                l_node = l_child;
+            }
+
+            // 3. Playout
+            let l_outcome = self.playout(&mut l_game);
+
+            // 4. Backpropagation
+            let mut l_current = Some(l_node);
+            while let Some(l_index) = l_current {
+                let l_node = &mut l_tree[l_index];
+                l_node.visits += 1;
+                l_node.wins += match l_outcome {
+                    game::GameOutcome::Won(l_winner) if Some(l_winner) == l_node.player => 1.0,
+                    game::GameOutcome::Draw => 0.5,
+                    _ => 0.0,
+                };
+                l_current = l_node.parent;
+            }
+        }
+
+        let l_best = l_tree[0]
+            .children
+            .iter()
+            .max_by_key(|l_child| l_tree[**l_child].visits)
+            .map(|l_child| &l_tree[*l_child])?;
+
+        INFO!(
+            "[MCTS] Best move {:?} : {} wins for {} visits, {} positions in the tree",
+            l_best.move_played,
+            l_best.wins,
+            l_best.visits,
+            l_tree.len()
+        );
+
+        l_best.move_played
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The exploration constant of the UCT value, the higher the more the moves tried the least are selected
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// A position of the tree
+struct Node {
+    /// The move leading to this position, `None` for the root
+    move_played: Option<(usize, usize)>,
+    /// The player who played this move, `None` for the root
+    player: Option<common::Player>,
+    /// The index of the previous position in the tree
+    parent: Option<usize>,
+    /// The indexes of the next positions in the tree
+    children: Vec<usize>,
+    /// The moves not added to the tree yet
+    untried: Vec<(usize, usize)>,
+    /// The number of playouts through this position
+    visits: u32,
+    /// The number of these playouts won by the player of the move, a draw counting as half a win
+    wins: f64,
+}
+
+impl Node {
+    /// Create the node of the game after the given move.
+    fn new(p_move: Option<(usize, usize)>, p_parent: Option<usize>, p_game: &game::Game) -> Self {
+        Node {
+            move_played: p_move,
+            player: p_move.map(|_| p_game.current_player().opponent()),
+            parent: p_parent,
+            children: Vec::new(),
+            untried: if p_game.is_over() {
+                Vec::new()
+            } else {
+                p_game.legal_moves()
+            },
+            visits: 0,
+            wins: 0.0,
+        }
+    }
+}
+
+impl Search {
+    /// Finish the game with random moves, and return how it ends.
+    fn playout(&mut self, p_game: &mut game::Game) -> game::GameOutcome {
+        loop {
+            let l_outcome = p_game.outcome();
+            if l_outcome != game::GameOutcome::InProgress {
+                return l_outcome;
+            }
+
+            let l_cells = p_game.legal_moves();
+            let (l_x, l_y) = l_cells[self.random.below(l_cells.len())];
+            p_game
+                .play(l_x, l_y)
+                .expect("[MCTS] A free cell can always be filled");
+        }
+    }
+}
+
+/// Return the child of the node with the best UCT value.
+fn select_child(p_tree: &[Node], p_node: usize) -> usize {
+    let l_parent_visits = f64::from(p_tree[p_node].visits.max(1)).ln();
+
+    *p_tree[p_node]
+        .children
+        .iter()
+        .max_by(|l_first, l_second| {
+            uct(&p_tree[**l_first], l_parent_visits)
+                .partial_cmp(&uct(&p_tree[**l_second], l_parent_visits))
+                .expect("[MCTS] The UCT value is a number")
+        })
+        .expect("[MCTS] The node has children")
+}
+
+/// Return the UCT value of a node, `p_parent_visits` being the logarithm of the number of visits of its parent.
+fn uct(p_node: &Node, p_parent_visits: f64) -> f64 {
+    let l_visits = f64::from(p_node.visits.max(1));
+    (p_node.wins / l_visits) + EXPLORATION * (p_parent_visits / l_visits).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return a 3x3 game after the given moves, played in turn from player one
+    fn game_after(p_moves: &[(usize, usize)]) -> game::Game {
+        let mut l_game = game::Game::new(3, 3, 3);
+        for (l_x, l_y) in p_moves {
+            l_game.play(*l_x, *l_y).unwrap();
+        }
+        l_game
+    }
+
+    #[test]
+    fn same_seed_same_move() {
+        let l_game = game_after(&[(0, 0), (1, 1)]);
+
+        let l_move = Search::new(500, Random::new(42)).best_move(&l_game);
+        for _ in 0..3 {
+            assert_eq!(Search::new(500, Random::new(42)).best_move(&l_game), l_move);
+        }
+    }
+
+    #[test]
+    fn finds_immediate_win() {
+        // X X .
+        // O O .
+        // . . .
+        let l_game = game_after(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+
+        assert_eq!(
+            Search::new(2000, Random::new(42)).best_move(&l_game),
+            Some((0, 2))
+        );
+    }
+
+    #[test]
+    fn blocks_forced_move() {
+        // X X .
+        // . O .
+        // . . .
+        let l_game = game_after(&[(0, 0), (1, 1), (0, 1)]);
+
+        assert_eq!(
+            Search::new(2000, Random::new(42)).best_move(&l_game),
+            Some((0, 2))
+        );
+    }
+
+    #[test]
+    fn no_move_when_over() {
+        let l_game = game_after(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+
+        assert_eq!(Search::new(100, Random::new(42)).best_move(&l_game), None);
+    }
+}
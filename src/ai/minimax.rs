@@ -0,0 +1,130 @@
+//! The minimax search of the best move of a [`game::Game`].
+//!
+//! Every continuation of the game is played until its end, or until the maximal depth. The player to play picks the
+//! move leading to its best score, knowing that the opponent will do the same.
+//! A win is worth more the sooner it happens, so the computer wins as fast as possible, and loses as late as possible.
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::{game, DEBUG, TRACE};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The score of a win at the root of the search, a win `n` moves later is worth `WIN_SCORE - n`
+pub const WIN_SCORE: i32 = 1_000;
+
+/// Return the coordinates `(row, column)` of the best move for the current player of the game, or `None` if the game
+/// is over.
+///
+/// With `p_max_depth`, only the given number of moves are searched, the positions not finished at this depth are
+/// considered as draw.
+pub fn best_move(p_game: &game::Game, p_max_depth: Option<usize>) -> Option<(usize, usize)> {
+    if p_game.is_over() {
+        return None;
+    }
+
+    let mut l_game = p_game.clone();
+    let mut l_best: Option<((usize, usize), i32)> = None;
+
+    for (l_x, l_y) in p_game.legal_moves() {
+        l_game.play(l_x, l_y).expect("[AI] A free cell can always be filled");
+        let l_score = -negamax(&mut l_game, 1, p_max_depth);
+        l_game.undo();
+
+        DEBUG!("[Minimax] Score of ({}, {}) : {}", l_x, l_y, l_score);
+
+        if l_best.is_none_or(|(_, l_best_score)| l_score > l_best_score) {
+            l_best = Some(((l_x, l_y), l_score));
+        }
+    }
+
+    l_best.map(|(l_move, _)| l_move)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Return the score of the position for the current player, `p_depth` moves after the root of the search.
+fn negamax(p_game: &mut game::Game, p_depth: usize, p_max_depth: Option<usize>) -> i32 {
+    match p_game.outcome() {
+        game::GameOutcome::Won(l_winner) => {
+            let l_score = WIN_SCORE - p_depth as i32;
+            return if l_winner == p_game.current_player() {
+                l_score
+            } else {
+                -l_score
+            };
+        }
+        game::GameOutcome::Draw => return 0,
+        game::GameOutcome::InProgress => {}
+    }
+
+    if p_max_depth.is_some_and(|l_max_depth| p_depth >= l_max_depth) {
+        return 0;
+    }
+
+    let mut l_best_score = -WIN_SCORE;
+    for (l_x, l_y) in p_game.legal_moves() {
+        p_game.play(l_x, l_y).expect("[AI] A free cell can always be filled");
+        l_best_score = l_best_score.max(-negamax(p_game, p_depth + 1, p_max_depth));
+        p_game.undo();
+    }
+
+    l_best_score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common;
+
+    /// Play every reply of player two to the moves of the search, and check that player two never wins
+    fn never_loses(p_game: &mut game::Game) {
+        if let Some((l_x, l_y)) = best_move(p_game, None) {
+            p_game.play(l_x, l_y).unwrap();
+            if !p_game.is_over() {
+                for (l_reply_x, l_reply_y) in p_game.free_cells() {
+                    p_game.play(l_reply_x, l_reply_y).unwrap();
+                    never_loses(p_game);
+                    p_game.undo();
+                }
+            }
+            p_game.undo();
+        }
+
+        assert_ne!(
+            p_game.outcome(),
+            game::GameOutcome::Won(common::Player::PlayerTwo)
+        );
+    }
+
+    #[test]
+    fn immediate_win_and_block() {
+        let l_game = game::Game::from_notation("3x3 3 XX./OO./... O").unwrap();
+        assert_eq!(best_move(&l_game, None), Some((1, 2)));
+
+        let l_game = game::Game::from_notation("3x3 3 X../OO./..X X").unwrap();
+        assert_eq!(best_move(&l_game, None), Some((1, 2)));
+    }
+
+    #[test]
+    fn never_loses_from_empty_grid() {
+        never_loses(&mut game::Game::new(3, 3, 3));
+    }
+
+    #[test]
+    fn max_depth_limits_search() {
+        // One move ahead, the threat of player two is not seen and the first free cell is chosen
+        let l_game = game::Game::from_notation("3x3 3 X../OO./..X X").unwrap();
+        assert_eq!(best_move(&l_game, Some(1)), Some((0, 1)));
+        assert_eq!(best_move(&l_game, Some(2)), Some((1, 2)));
+    }
+}
@@ -0,0 +1,364 @@
+//! Module in charge of the computer opponent.
+//!
+//! The computer plays the turn of a player of a [`game::Game`] in place of a human, see [`play_turn`].
+//! The move is chosen by a search of the possible continuations of the game, with the [`Engine`] given in the
+//! [`Settings`]:
+//! - [`Engine::AlphaBeta`]: the whole game is searched with [`minimax`] when there are few free cells, otherwise an
+//!   [`alpha_beta`] search is made within a budget
+//! - [`Engine::MonteCarlo`]: a [`mcts`] search, needing no knowledge of the game, made of a number of random games
+//!
+//! The same searches give hints to the human players, see [`hint`].
+//!
+//! The [`Level`] of the computer makes it weaker: below [`Level::Perfect`], some moves are random and the search is
+//! shorter.
+//!
+//! # Example
+//!
+//! ```rust
+//! mod ai;
+//!
+//! let screen = screen::Screen::new_and_start();
+//! let mut game = game::Game::new(3, 3, 3);
+//! let settings = ai::Settings::default();
+//!
+//! while game.is_over() != true {
+//!     ai::play_turn(&screen, &mut game, &settings);
+//!     game.toggle_player();
+//! }
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+pub mod alpha_beta;
+pub mod hint;
+pub mod mcts;
+pub mod minimax;
+pub mod random;
+
+use crate::{game, screen, DEBUG, INFO, TRACE};
+use std::str::FromStr;
+use std::time::Duration;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The default number of random games of a [`Engine::MonteCarlo`] search for each move
+pub const DEFAULT_ITERATIONS: u32 = 10_000;
+
+/// The search used by the computer to choose its moves
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Engine {
+    /// A [`minimax`] search on the small grids, an [`alpha_beta`] search spending the given budget on the others
+    AlphaBeta(alpha_beta::Budget),
+    /// A [`mcts`] search of the given number of iterations
+    MonteCarlo(u32),
+}
+
+/// The level of the computer, the lower the more often it plays a random move and the shorter it searches
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Level {
+    /// Half of the moves are random, the others only see the wins in one move
+    Easy,
+    /// A quarter of the moves are random, the others see the wins and the threats in one move
+    Medium,
+    /// Some moves are random, the others are searched four moves ahead
+    Hard,
+    /// The moves are searched as far as the budget allows it
+    Perfect,
+}
+
+impl Level {
+    /// Return the name of the level, as shown to the players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Level::Easy => "Easy",
+            Level::Medium => "Medium",
+            Level::Hard => "Hard",
+            Level::Perfect => "Perfect",
+        }
+    }
+
+    /// Return the probability, in percent, to play a random move
+    fn random_move_percentage(&self) -> u64 {
+        match self {
+            Level::Easy => 50,
+            Level::Medium => 25,
+            Level::Hard => 5,
+            Level::Perfect => 0,
+        }
+    }
+
+    /// Return the number of moves searched ahead, `None` for no limit
+    fn max_depth(&self) -> Option<usize> {
+        match self {
+            Level::Easy => Some(1),
+            Level::Medium => Some(2),
+            Level::Hard => Some(4),
+            Level::Perfect => None,
+        }
+    }
+
+    /// Return the part of the iterations of a [`mcts`] search made at this level, `1 / <returned value>`
+    fn iterations_divisor(&self) -> u32 {
+        match self {
+            Level::Easy => 100,
+            Level::Medium => 10,
+            Level::Hard => 2,
+            Level::Perfect => 1,
+        }
+    }
+}
+
+/// The settings of the computer player
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Settings {
+    /// The search used to choose the moves
+    engine: Engine,
+    /// The level of the computer
+    level: Level,
+    /// The seed of the random generator, to reproduce the games, otherwise the time is used
+    seed: Option<u64>,
+}
+
+impl Settings {
+    /// Create the settings of a perfect computer player choosing its moves with the given engine.
+    pub fn new(p_engine: Engine) -> Self {
+        Settings {
+            engine: p_engine,
+            level: Level::Perfect,
+            seed: None,
+        }
+    }
+
+    /// Set the level of the computer.
+    pub fn with_level(mut self, p_level: Level) -> Self {
+        self.level = p_level;
+        self
+    }
+
+    /// Return the level of the computer.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Set the seed of the random generator, so that the same position is always given the same move.
+    pub fn with_seed(mut self, p_seed: u64) -> Self {
+        self.seed = Some(p_seed);
+        self
+    }
+}
+
+/// Read settings written `<engine>[:<budget>][:<level>][:seed=<n>]`, for example `ab:500ms:hard` or `mc:2000:seed=42`:
+/// - the engine is `ab` for [`Engine::AlphaBeta`] or `mc` for [`Engine::MonteCarlo`]
+/// - the budget is the one of an alpha-beta search, see [`alpha_beta::Budget`], or the number of iterations of a
+///   Monte Carlo search, by default one second or [`DEFAULT_ITERATIONS`]
+/// - the level is `easy`, `medium`, `hard` or `perfect`, by default `perfect`
+/// - the seed of the random generator, see [`Settings::with_seed`], by default the time is used
+impl FromStr for Settings {
+    type Err = String;
+
+    fn from_str(p_text: &str) -> Result<Self, Self::Err> {
+        let l_text = p_text.trim().to_lowercase();
+        let mut l_parts = l_text.split(':');
+        let mut l_settings = match l_parts.next() {
+            Some("ab") => Settings::default(),
+            Some("mc") => Settings::new(Engine::MonteCarlo(DEFAULT_ITERATIONS)),
+            _ => {
+                return Err(format!(
+                    "`{}` is not an engine, ab or mc expected",
+                    p_text.trim()
+                ))
+            }
+        };
+
+        for l_part in l_parts {
+            l_settings = match (l_part, l_settings.engine) {
+                ("easy", _) => l_settings.with_level(Level::Easy),
+                ("medium", _) => l_settings.with_level(Level::Medium),
+                ("hard", _) => l_settings.with_level(Level::Hard),
+                ("perfect", _) => l_settings.with_level(Level::Perfect),
+                _ if l_part.starts_with("seed=") => match l_part["seed=".len()..].parse::<u64>() {
+                    Ok(l_seed) => l_settings.with_seed(l_seed),
+                    Err(_) => {
+                        return Err(format!(
+                            "`{}` is not a seed, seed=<number> expected",
+                            l_part
+                        ))
+                    }
+                },
+                (_, Engine::AlphaBeta(_)) => Settings {
+                    engine: Engine::AlphaBeta(l_part.parse()?),
+                    ..l_settings
+                },
+                (_, Engine::MonteCarlo(_)) => match l_part.parse::<u32>() {
+                    Ok(l_iterations) if l_iterations > 0 => Settings {
+                        engine: Engine::MonteCarlo(l_iterations),
+                        ..l_settings
+                    },
+                    _ => {
+                        return Err(format!(
+                            "`{}` is not a level or a number of iterations",
+                            l_part
+                        ))
+                    }
+                },
+            };
+        }
+
+        Ok(l_settings)
+    }
+}
+
+impl Default for Settings {
+    /// A perfect computer player spending one second for each move
+    fn default() -> Self {
+        Settings::new(Engine::AlphaBeta(alpha_beta::Budget::Time(
+            Duration::from_secs(1),
+        )))
+    }
+}
+
+/// Plays the current player's turn of [`game::Game`] with the move chosen by the computer.
+///
+/// Return [`game::TurnStatus::Played`] once the move is made, or [`game::TurnStatus::Quit`] if there is no move to
+/// play.
+pub fn play_turn(
+    p_screen: &screen::Screen,
+    p_game: &mut game::Game,
+    p_settings: &Settings,
+) -> game::TurnStatus {
+    let l_best_move = best_move(p_game, p_settings);
+
+    match l_best_move {
+        Some((l_x, l_y)) => {
+            let l_player = p_game.current_player();
+            match p_game.set_cell(l_x, l_y, l_player) {
+                Ok(()) => {
+                    INFO!("[AI] {:?} plays ({}, {})", l_player, l_x, l_y);
+                    if p_game.has_gravity() {
+                        p_screen.send_msg(&format!("The computer plays in the column {}", l_y));
+                    } else {
+                        p_screen.send_msg(&format!(
+                            "The computer fills the cell {}",
+                            (l_x * p_game.columns()) + l_y
+                        ));
+                    }
+                    game::TurnStatus::Played
+                }
+                Err(l_error) => {
+                    INFO!("[AI] The move ({}, {}) is refused, {}", l_x, l_y, l_error);
+                    game::TurnStatus::Quit
+                }
+            }
+        }
+        None => game::TurnStatus::Quit,
+    }
+}
+
+/// Return the coordinates `(row, column)` of the move chosen by the computer for the current player of the game, or
+/// `None` if the game is over.
+///
+/// According to the [`Level`], the move may be a random one, or be searched only a few moves ahead.
+pub fn best_move(p_game: &game::Game, p_settings: &Settings) -> Option<(usize, usize)> {
+    let mut l_random = match p_settings.seed {
+        // The position is part of the seed, so that the same random numbers are not used for every move
+        Some(l_seed) => random::Random::new(l_seed ^ p_game.position_hash()),
+        None => random::Random::from_time(),
+    };
+    let l_level = p_settings.level;
+
+    let l_moves = p_game.legal_moves();
+    if p_game.is_over() || l_moves.is_empty() {
+        return None;
+    }
+    if l_random.below(100) < l_level.random_move_percentage() as usize {
+        DEBUG!("[AI] Random move at the level {}", l_level.name());
+        return Some(l_moves[l_random.below(l_moves.len())]);
+    }
+
+    match p_settings.engine {
+        Engine::AlphaBeta(_) if p_game.free_cells().len() <= FULL_SEARCH_CELLS => {
+            minimax::best_move(p_game, l_level.max_depth())
+        }
+        Engine::AlphaBeta(l_budget) => {
+            let mut l_search = alpha_beta::Search::new(l_budget);
+            if let Some(l_max_depth) = l_level.max_depth() {
+                l_search = l_search.with_max_depth(l_max_depth);
+            }
+            l_search.best_move(p_game).map(|(l_move, _)| l_move)
+        }
+        Engine::MonteCarlo(l_iterations) => mcts::Search::new(
+            (l_iterations / l_level.iterations_divisor()).max(1),
+            l_random,
+        )
+        .best_move(p_game),
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Up to this number of free cells, the whole game is searched, and the computer plays perfectly
+const FULL_SEARCH_CELLS: usize = 9;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_from_str() {
+        assert_eq!(
+            "mc:2000:hard:seed=42".parse::<Settings>(),
+            Ok(Settings::new(Engine::MonteCarlo(2000))
+                .with_level(Level::Hard)
+                .with_seed(42))
+        );
+        assert_eq!("ab".parse::<Settings>(), Ok(Settings::default()));
+        assert!("ab:seed=x".parse::<Settings>().is_err());
+        assert!("xy".parse::<Settings>().is_err());
+    }
+
+    #[test]
+    fn seeded_moves_are_reproduced() {
+        let mut l_game = game::Game::new(4, 4, 3);
+        l_game.play(1, 1).unwrap();
+        let l_settings = Settings::new(Engine::MonteCarlo(200))
+            .with_level(Level::Easy)
+            .with_seed(7);
+
+        let l_move = best_move(&l_game, &l_settings);
+        assert!(l_move.is_some());
+        for _ in 0..5 {
+            assert_eq!(best_move(&l_game, &l_settings), l_move);
+        }
+    }
+
+    #[test]
+    fn misere_avoids_completing_a_line() {
+        let l_cases = [
+            ("3x3 3 XX./OO./... X misere", Settings::default()),
+            (
+                "4x4 3 XX../OO../..../.... X misere",
+                Settings::new(Engine::AlphaBeta(alpha_beta::Budget::Nodes(20_000))),
+            ),
+            (
+                "4x4 3 XX../OO../..../.... X misere",
+                Settings::new(Engine::MonteCarlo(2000)).with_seed(3),
+            ),
+        ];
+
+        for (l_notation, l_settings) in &l_cases {
+            let mut l_game = game::Game::from_notation(l_notation).unwrap();
+            let (l_x, l_y) = best_move(&l_game, l_settings).unwrap();
+            l_game.play(l_x, l_y).unwrap();
+            assert_eq!(l_game.victory(), None);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! A small pseudo-random number generator, seedable so that the games of the computer can be reproduced.
+//!
+//! It is a _xorshift64*_ generator: fast and good enough to choose moves, but not for cryptography.
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The state of the generator
+#[derive(Debug, Clone)]
+pub struct Random {
+    /// The current state, never 0
+    state: u64,
+}
+
+impl Random {
+    /// Create a generator, always giving the same numbers for the same seed.
+    pub fn new(p_seed: u64) -> Self {
+        // The seed is mixed so that close seeds give different numbers, and the state is never 0
+        let l_state = mix(p_seed);
+
+        Random {
+            state: if l_state == 0 { 1 } else { l_state },
+        }
+    }
+
+    /// Create a generator seeded with the current time.
+    pub fn from_time() -> Self {
+        Random::new(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|l_duration| l_duration.as_nanos() as u64)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Return the next number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Return a number between 0 included and `p_max` excluded, `p_max` must not be 0.
+    pub fn below(&mut self, p_max: usize) -> usize {
+        (self.next_u64() % p_max as u64) as usize
+    }
+}
+
+/// Return a pseudo-random number for the given one, the same each time (_SplitMix64_ finalizer).
+///
+/// Close numbers give very different results, it seeds the generator and gives each cell its number in the hash of
+/// the positions, see [`crate::game::Game::position_hash`].
+pub fn mix(p_value: u64) -> u64 {
+    let mut l_value = p_value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    l_value = (l_value ^ (l_value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    l_value = (l_value ^ (l_value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    l_value ^ (l_value >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut l_first = Random::new(42);
+        let mut l_second = Random::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(l_first.next_u64(), l_second.next_u64());
+        }
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut l_random = Random::new(7);
+
+        assert!((0..1000).all(|_| l_random.below(9) < 9));
+    }
+
+    #[test]
+    fn mix_gives_distinct_numbers() {
+        let l_numbers: HashSet<u64> = (0..1000).map(mix).collect();
+
+        assert_eq!(l_numbers.len(), 1000);
+        assert!(!l_numbers.contains(&0));
+    }
+}
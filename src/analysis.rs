@@ -0,0 +1,283 @@
+//! Module in charge of the analysis of a position.
+//!
+//! A position, typed in the notation of the [game] module or loaded from a file, is searched with the
+//! [`alpha_beta`] search, and every move of the player to play is listed with its value:
+//! - a win or a loss, in a number of moves, when the search reached the end of the game
+//! - a draw, when every continuation was searched up to the full grid
+//! - otherwise the evaluation of the position by the search, and the number of moves searched ahead
+//!
+//! The moves are listed from the best to the worst.
+//!
+//! # Example
+//!
+//! ```rust
+//! mod analysis;
+//!
+//! let screen = screen::Screen::new_and_start();
+//! let game = game::Game::from_notation("3x3 3 X../.O./... X").unwrap();
+//!
+//! analysis::analyse(&screen, &game, analysis::DEFAULT_BUDGET);
+//! screen.stop_and_free();
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::ai::alpha_beta;
+use crate::{common, game, screen, DEBUG, INFO, TRACE};
+use std::cmp::Reverse;
+use std::time::Duration;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The resources spent to search each move, when no other budget is given
+pub const DEFAULT_BUDGET: alpha_beta::Budget = alpha_beta::Budget::Time(Duration::from_secs(1));
+
+/// The game-theoretic value of a move, for the player making it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Value {
+    /// The player wins, the last move of the game being the given number of moves later, this one included
+    Win(usize),
+    /// The game is drawn once the given number of moves are played, this one included
+    Draw(usize),
+    /// The player loses, the last move of the game being the given number of moves later, this one included
+    Loss(usize),
+    /// The search did not reach the end of the game: the evaluation of the position and the number of moves searched
+    Unknown(i32, usize),
+}
+
+impl Value {
+    /// Return the rank of the value, the greater the better for the player, to sort the moves
+    fn rank(&self) -> (i32, i64) {
+        match *self {
+            // The sooner the better
+            Value::Win(l_moves) => (3, -(l_moves as i64)),
+            Value::Unknown(l_evaluation, _) if l_evaluation > 0 => (2, l_evaluation as i64),
+            Value::Draw(_) => (1, 0),
+            Value::Unknown(l_evaluation, _) => (1, l_evaluation as i64),
+            // The later the better
+            Value::Loss(l_moves) => (0, l_moves as i64),
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Win(l_moves) => write!(f, "\x1B[32mwin in {} moves\x1B[0m", l_moves),
+            Value::Draw(l_moves) => write!(f, "draw in {} moves", l_moves),
+            Value::Loss(l_moves) => write!(f, "\x1B[31mloss in {} moves\x1B[0m", l_moves),
+            Value::Unknown(l_evaluation, l_depth) => write!(
+                f,
+                "unknown, evaluation {} ({} moves searched ahead)",
+                l_evaluation, l_depth
+            ),
+        }
+    }
+}
+
+/// Ask a position in the notation of the [game] module, see [`game::Game::from_notation`].
+///
+/// Returns `None` if the user enters `q`, as long as a valid position has not been entered, the user will be asked
+/// again.
+pub fn ask_position(p_screen: &screen::Screen) -> Option<game::Game> {
+    p_screen.send_msg(
+        "Enter the position to analyse, for example \x1B[44m3x3 3 X../.O./... X\x1B[0m, or q to quit :",
+    );
+
+    loop {
+        let l_entry = game::read_keyboard();
+        if l_entry.trim().eq_ignore_ascii_case("q") {
+            return None;
+        }
+
+        match game::Game::from_notation(l_entry.trim()) {
+            Ok(l_game) => return Some(l_game),
+            Err(l_error) => p_screen.send_msg(&format!(
+                "\x1B[41mBad position, {}, please retry\x1B[0m",
+                l_error
+            )),
+        }
+    }
+}
+
+/// Display the position on the [`screen::Screen`], with the value of every move of the player to play.
+///
+/// Each move is searched within the given budget, the moves are listed from the best to the worst.
+pub fn analyse(p_screen: &screen::Screen, p_game: &game::Game, p_budget: alpha_beta::Budget) {
+    p_screen.send_game(p_game);
+
+    match p_game.outcome() {
+        game::GameOutcome::Won(l_winner) => {
+            p_screen.send_msg(&format!(
+                "The game is over, won by {}",
+                player_name(l_winner)
+            ));
+            return;
+        }
+        game::GameOutcome::Draw => {
+            p_screen.send_msg("The game is over, it is a draw");
+            return;
+        }
+        game::GameOutcome::InProgress => {}
+    }
+
+    p_screen.send_msg(&format!(
+        "Analysis of the moves of {}, please wait...",
+        player_name(p_game.current_player())
+    ));
+
+    // The table of the search is kept from a move to the next one, the positions reached are often the same
+    let mut l_search = alpha_beta::Search::new(p_budget).without_pruning();
+    let mut l_values: Vec<_> = p_game
+        .legal_moves()
+        .into_iter()
+        .map(|(l_x, l_y)| {
+            let (l_value, l_reply) = move_value(&mut l_search, p_game, l_x, l_y);
+            let l_move = move_name(p_game, l_x, l_y);
+            DEBUG!("[Analysis] {} : {:?}, reply {:?}", l_move, l_value, l_reply);
+            (l_move, l_value, l_reply)
+        })
+        .collect();
+
+    l_values.sort_by_key(|(_, l_value, _)| Reverse(l_value.rank()));
+
+    for (l_move, l_value, l_reply) in l_values {
+        match l_reply {
+            Some((l_reply_x, l_reply_y)) => p_screen.send_msg(&format!(
+                "{} : {}, best reply {}",
+                l_move,
+                l_value,
+                move_name(p_game, l_reply_x, l_reply_y)
+            )),
+            None => p_screen.send_msg(&format!("{} : {}", l_move, l_value)),
+        }
+    }
+
+    INFO!("[Analysis] Position {} analysed", p_game.to_notation());
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Return the value of the move `(p_x, p_y)` for the player to play, and the coordinates `(row, column)` of the best
+/// reply of the opponent, if any.
+fn move_value(
+    p_search: &mut alpha_beta::Search,
+    p_game: &game::Game,
+    p_x: usize,
+    p_y: usize,
+) -> (Value, Option<(usize, usize)>) {
+    let mut l_game = p_game.clone();
+    if l_game.play(p_x, p_y).is_err() {
+        // The cells analysed are the free ones, it can not happen
+        return (Value::Unknown(0, 0), None);
+    }
+
+    match l_game.outcome() {
+        // With the misère rules, the player completing a line loses
+        game::GameOutcome::Won(l_winner) if l_winner == p_game.current_player() => {
+            return (Value::Win(1), None)
+        }
+        game::GameOutcome::Won(_) => return (Value::Loss(1), None),
+        game::GameOutcome::Draw => return (Value::Draw(1), None),
+        game::GameOutcome::InProgress => {}
+    }
+
+    let l_analysis = match p_search.analyse(&l_game) {
+        Some(l_analysis) => l_analysis,
+        None => return (Value::Unknown(0, 0), None),
+    };
+    let l_reply = Some(l_analysis.best_move());
+
+    // The score is the one of the opponent, a win `n` moves after the reply is worth `WIN_SCORE - n`
+    let l_score = -l_analysis.score();
+    let l_moves = 1 + (alpha_beta::WIN_SCORE - l_score.abs()) as usize;
+    let l_end_reached =
+        l_score.abs() > alpha_beta::WIN_SCORE - (l_game.free_cells().len() as i32) - 1;
+
+    let l_value = if l_end_reached && l_score > 0 {
+        Value::Win(l_moves)
+    } else if l_end_reached {
+        Value::Loss(l_moves)
+    } else if l_analysis.is_solved() && l_score == 0 {
+        Value::Draw(p_game.free_cells().len())
+    } else {
+        Value::Unknown(l_score, l_analysis.depth())
+    };
+
+    (l_value, l_reply)
+}
+
+/// Return the name of the move `(p_x, p_y)`, as entered by the players: the number of its cell, or of its column with
+/// the gravity.
+fn move_name(p_game: &game::Game, p_x: usize, p_y: usize) -> String {
+    if p_game.has_gravity() {
+        format!("column {}", p_y)
+    } else {
+        format!("cell {}", (p_x * p_game.columns()) + p_y)
+    }
+}
+
+/// Return the name of the player, as shown to the players
+fn player_name(p_player: common::Player) -> &'static str {
+    match p_player {
+        common::Player::PlayerOne => "player one",
+        common::Player::PlayerTwo => "player two",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_of_the_moves() {
+        let l_game = game::Game::from_notation("3x3 3 XX./OO./... X").unwrap();
+        let mut l_search = alpha_beta::Search::new(DEFAULT_BUDGET).without_pruning();
+
+        assert_eq!(
+            move_value(&mut l_search, &l_game, 0, 2),
+            (Value::Win(1), None)
+        );
+        assert_eq!(
+            move_value(&mut l_search, &l_game, 2, 0),
+            (Value::Loss(2), Some((1, 2)))
+        );
+    }
+
+    #[test]
+    fn names_of_the_moves() {
+        let l_game = game::Game::new(3, 4, 3);
+        assert_eq!(move_name(&l_game, 2, 1), "cell 9");
+
+        let l_game = l_game.with_gravity(true);
+        assert_eq!(move_name(&l_game, 2, 1), "column 1");
+    }
+
+    #[test]
+    fn draw_value() {
+        let l_game = game::Game::from_notation("3x3 3 XOX/XOO/O.. X").unwrap();
+        let mut l_search = alpha_beta::Search::new(DEFAULT_BUDGET).without_pruning();
+
+        assert_eq!(move_value(&mut l_search, &l_game, 2, 1).0, Value::Draw(2));
+    }
+
+    #[test]
+    fn misere_values() {
+        let l_game = game::Game::from_notation("3x3 3 XX./OO./... X misere").unwrap();
+        let mut l_search = alpha_beta::Search::new(DEFAULT_BUDGET).without_pruning();
+
+        assert_eq!(
+            move_value(&mut l_search, &l_game, 0, 2),
+            (Value::Loss(1), None)
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Module in charge of the games between two computers, to compare their settings.
+//!
+//! The two [`ai::Settings`] play a number of games against each other, headless, with the game loop of the
+//! [`state_machine`]. They start in turn: the first settings play player one in the even games, and player two in the
+//! odd ones. At the end, the wins, draws and losses of the first settings are displayed.
+//!
+//! # Example
+//!
+//! ```rust
+//! mod arena;
+//!
+//! let game = game::Game::new(3, 3, 3);
+//! let engines = [ai::Settings::default(), "mc:2000".parse::<ai::Settings>().unwrap()];
+//!
+//! let score = arena::run(&game, &engines, 10);
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::{ai, common, game, player, screen, state_machine, INFO, TRACE};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The results of the games, for the first settings
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Score {
+    /// The games won by the first settings
+    wins: u32,
+    /// The drawn games
+    draws: u32,
+    /// The games won by the second settings
+    losses: u32,
+}
+
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "\x1B[32m{} win(s)\x1B[0m, {} draw(s), \x1B[31m{} loss(es)\x1B[0m",
+            self.wins, self.draws, self.losses
+        )
+    }
+}
+
+/// Play `p_games` games from the position `p_game` between the two settings, starting in turn, see the [module](self).
+///
+/// The result of each game and the final score are printed, the score is returned.
+pub fn run(p_game: &game::Game, p_engines: &[ai::Settings; 2], p_games: u32) -> Score {
+    let mut l_score = Score::default();
+
+    for i in 0..p_games {
+        // The index of the settings playing player one
+        let l_first = (i % 2) as usize;
+        let l_setup = state_machine::Setup::new()
+            .with_game(p_game.clone())
+            .with_player(
+                common::Player::PlayerOne,
+                Box::new(player::Computer::new(p_engines[l_first])),
+            )
+            .with_player(
+                common::Player::PlayerTwo,
+                Box::new(player::Computer::new(p_engines[1 - l_first])),
+            )
+            .headless();
+
+        let l_state_machine = state_machine::StateMachine::new_and_start(l_setup);
+        l_state_machine.start_game();
+        let l_outcome = l_state_machine.wait_end_game();
+
+        let l_winner = match l_outcome {
+            game::GameOutcome::Won(common::Player::PlayerOne) => Some(l_first),
+            game::GameOutcome::Won(common::Player::PlayerTwo) => Some(1 - l_first),
+            game::GameOutcome::Draw | game::GameOutcome::InProgress => None,
+        };
+        match l_winner {
+            Some(0) => l_score.wins += 1,
+            Some(_) => l_score.losses += 1,
+            None => l_score.draws += 1,
+        }
+
+        INFO!("[Arena] Game {} : {:?}", i + 1, l_outcome);
+        screen::print(&format!(
+            "Game {}/{} : {} plays first, {}",
+            i + 1,
+            p_games,
+            ENGINE_NAMES[l_first],
+            match l_winner {
+                Some(l_winner) => format!("{} wins", ENGINE_NAMES[l_winner]),
+                None => String::from("draw"),
+            }
+        ));
+    }
+
+    screen::print(&format!("Engine one against engine two : {}", l_score));
+    l_score
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The names of the two settings, as printed
+const ENGINE_NAMES: [&str; 2] = ["engine one", "engine two"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_game_is_counted() {
+        let l_engines = [
+            "mc:300:seed=1".parse::<ai::Settings>().unwrap(),
+            "mc:300:seed=2".parse::<ai::Settings>().unwrap(),
+        ];
+        let l_score = run(&game::Game::new(3, 3, 3), &l_engines, 5);
+
+        assert_eq!(l_score.wins + l_score.draws + l_score.losses, 5);
+    }
+
+    #[test]
+    fn seats_swap() {
+        // Whoever plays player one wins at once
+        let l_game = game::Game::from_notation("3x3 3 XX./OO./... X").unwrap();
+        let l_engines = [
+            ai::Settings::default().with_seed(1),
+            ai::Settings::default().with_seed(2),
+        ];
+
+        assert_eq!(
+            run(&l_game, &l_engines, 4),
+            Score {
+                wins: 2,
+                draws: 0,
+                losses: 2
+            }
+        );
+    }
+}
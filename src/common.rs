@@ -4,6 +4,9 @@
 //! - Pierre-Louis GAUTIER
 //! - Damien FRISSANT
 
+use crate::ai::random;
+use std::str::FromStr;
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// Enumeration containing the different roles of the players
 pub enum Player {
@@ -13,8 +16,136 @@ pub enum Player {
     PlayerTwo,
 }
 
+impl Player {
+    /// Return the opponent of the player
+    pub fn opponent(&self) -> Player {
+        match self {
+            Player::PlayerOne => Player::PlayerTwo,
+            Player::PlayerTwo => Player::PlayerOne,
+        }
+    }
+}
+
+/// Who starts the games, see [`FirstPlayer::choose`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FirstPlayer {
+    /// The given player starts every game
+    Fixed(Player),
+    /// Player one starts the first game, then the players start in turn
+    Alternate,
+    /// The player starting each game is chosen by a coin toss
+    Random,
+}
+
+impl FirstPlayer {
+    /// Return the player starting the game of the given number, counted from 0.
+    pub fn choose(&self, p_game_number: u32) -> Player {
+        match self {
+            FirstPlayer::Fixed(l_player) => *l_player,
+            FirstPlayer::Alternate if p_game_number % 2 == 1 => Player::PlayerTwo,
+            FirstPlayer::Alternate => Player::PlayerOne,
+            FirstPlayer::Random if random::Random::from_time().below(2) == 1 => Player::PlayerTwo,
+            FirstPlayer::Random => Player::PlayerOne,
+        }
+    }
+}
+
+/// Read who starts the games: `1` or `2` for a fixed player, `alternate` or `random`.
+impl FromStr for FirstPlayer {
+    type Err = String;
+
+    fn from_str(p_text: &str) -> Result<Self, Self::Err> {
+        match p_text.trim().to_lowercase().as_str() {
+            "1" => Ok(FirstPlayer::Fixed(Player::PlayerOne)),
+            "2" => Ok(FirstPlayer::Fixed(Player::PlayerTwo)),
+            "alternate" => Ok(FirstPlayer::Alternate),
+            "random" => Ok(FirstPlayer::Random),
+            _ => Err(format!(
+                "`{}` is not 1, 2, alternate or random",
+                p_text.trim()
+            )),
+        }
+    }
+}
+
+/// The game played, chosen when the program starts
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Variant {
+    /// The tic-tac-toe on a grid of any size, see [`crate::game`]
+    Classic,
+    /// The tic-tac-toe of nine tic-tac-toe boards, see [`crate::ultimate`]
+    Ultimate,
+    /// The tic-tac-toe in a cube, see [`crate::qubic`]
+    Qubic,
+}
+
+/// Read the game played: `classic`, `ultimate` or `qubic`.
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(p_text: &str) -> Result<Self, Self::Err> {
+        match p_text.trim().to_lowercase().as_str() {
+            "classic" => Ok(Variant::Classic),
+            "ultimate" => Ok(Variant::Ultimate),
+            "qubic" => Ok(Variant::Qubic),
+            _ => Err(format!(
+                "`{}` is not classic, ultimate or qubic",
+                p_text.trim()
+            )),
+        }
+    }
+}
+
 /// The symbol associated with player one: a green X
 pub const PLAYER_ONE_SYMBOL: &str = "  \x1B[32mX\x1B[0m  ";
 
 /// The symbol associated with player two: a red O
 pub const PLAYER_TWO_SYMBOL: &str = "  \x1B[31mO\x1B[0m  ";
+
+/// The symbol of player one in a winning line: a X on a green background
+pub const PLAYER_ONE_WINNING_SYMBOL: &str = "\x1B[42m  X  \x1B[0m";
+
+/// The symbol of player two in a winning line: a O on a red background
+pub const PLAYER_TWO_WINNING_SYMBOL: &str = "\x1B[41m  O  \x1B[0m";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_player_of_each_game() {
+        let l_fixed = FirstPlayer::Fixed(Player::PlayerTwo);
+        assert!((0..10).all(|i| l_fixed.choose(i) == Player::PlayerTwo));
+
+        for i in 0..10 {
+            let l_expected = if i % 2 == 0 {
+                Player::PlayerOne
+            } else {
+                Player::PlayerTwo
+            };
+            assert_eq!(FirstPlayer::Alternate.choose(i), l_expected);
+        }
+    }
+
+    #[test]
+    fn first_player_from_str() {
+        assert_eq!(
+            "1".parse::<FirstPlayer>(),
+            Ok(FirstPlayer::Fixed(Player::PlayerOne))
+        );
+        assert_eq!(
+            " 2 ".parse::<FirstPlayer>(),
+            Ok(FirstPlayer::Fixed(Player::PlayerTwo))
+        );
+        assert_eq!(
+            "Alternate".parse::<FirstPlayer>(),
+            Ok(FirstPlayer::Alternate)
+        );
+        assert_eq!("RANDOM".parse::<FirstPlayer>(), Ok(FirstPlayer::Random));
+        assert_eq!(
+            "3".parse::<FirstPlayer>(),
+            Err(String::from("`3` is not 1, 2, alternate or random"))
+        );
+        assert!("".parse::<FirstPlayer>().is_err());
+    }
+}
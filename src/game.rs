@@ -3,6 +3,12 @@
 //! All information about the current game is saved in a [`Game`] instance. It is then possible to have several games
 //! at the same time.
 //!
+//! The grid is only made of [`Cell`], the way a cell is drawn is left to the display layer, see [screen].
+//!
+//! With the misère rules, the player completing a line loses the game instead of winning it, see
+//! [`Game::with_misere`]. With the gravity, the symbols fall to the bottom of the grid like in the _Connect Four_, the
+//! players choose a column instead of a cell, see [`Game::with_gravity`].
+//!
 //! # Example
 //!
 //! The `game` module is closely related to the [screen] module, but can be used independently of it.
@@ -12,14 +18,14 @@
 //! mod game;
 //! mod screen;
 //!
-//! let size_grid: usize = 3; // An integer greater than 2, and less than 10, preferably
-//! let mut game = Game::new(size_grid);
-//!
-//! println!(game);
+//! let rows: usize = 3; // An integer greater than 2, and less than 10, preferably
+//! let columns: usize = 3; // The grid does not have to be a square
+//! let win_length: usize = 3; // The number of aligned symbols needed to win, at most the biggest dimension
+//! let mut game = Game::new(rows, columns, win_length);
 //!
 //! while game.is_over() != true {
 //!     game.toggle_player();
-//!     game.set_cell(<x>, <y>, game.current_symbol());
+//!     game.set_cell(<x>, <y>, game.current_player());
 //! }
 //! ```
 //!
@@ -30,7 +36,7 @@
 //! mod screen;
 //!
 //! let screen = screen::Screen::new_and_start();
-//! let mut l_game: game::Game = game::create_game(&screen);
+//! let mut l_game: game::Game = game::create_game(&screen, None, None);
 //!
 //! while game.is_over() != true {
 //!     screen.send_game(&game);
@@ -42,12 +48,36 @@
 //! screen.stop_and_free();
 //! ```
 //!
+//! # Notation
+//!
+//! A position can be written on a single line of text, see [`Game::to_notation`] and [`Game::from_notation`].
+//! The line is made of four fields separated by spaces, followed by the rules of the game, if any:
+//!
+//! 1. the size of the grid: `<rows>x<columns>`
+//! 2. the number of aligned symbols needed to win
+//! 3. the cells, row by row from the top, the rows being separated by `/`: `X` for player one, `O` for player two and
+//!    `.` for an empty cell
+//! 4. the player who has to play: `X` or `O`
+//! 5. `misere` if the player completing a line loses, `gravity` if the symbols fall to the bottom of the grid, omitted
+//!    otherwise
+//!
+//! For example, a 3x3 game where player one has played the center and player two the top left corner, player one
+//! to play, is written:
+//!
+//! ```text
+//! 3x3 3 O../.X./... X
+//! ```
+//!
+//! The letters are not case sensitive. The history of the moves is not part of the notation.
+//!
 //! # Authors
 //! - Pierre-Louis GAUTIER
 //! - Damien FRISSANT
 
-use crate::{common, screen, DEBUG, TRACE};
+use crate::ai::{self, alpha_beta, random};
+use crate::{common, player, screen, DEBUG, TRACE};
 use std::fmt;
+use std::fs;
 use std::io::stdin;
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -56,52 +86,467 @@ use std::io::stdin;
 //
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The content of a cell of the grid.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Cell {
+    /// Nobody has played in this cell yet
+    Empty,
+    /// The cell has been filled by the given player
+    Occupied(common::Player),
+}
+
+/// The state of a game, see [`Game::outcome`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameOutcome {
+    /// No one has won yet and there are still free cells
+    InProgress,
+    /// The given player has filled a line
+    Won(common::Player),
+    /// The grid is full without any winner
+    Draw,
+}
+
+/// The description of a win: who won, and with which line(s), see [`Game::victory`].
+///
+/// With the misère rules, it is the description of a loss, see [`Game::with_misere`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Victory {
+    /// The player who aligned enough symbols
+    player: common::Player,
+    /// The coordinates `(row, column)` of the cells of each winning line
+    lines: Vec<Vec<(usize, usize)>>,
+}
+
+impl Victory {
+    /// Return the player who aligned enough symbols
+    pub fn player(&self) -> common::Player {
+        self.player
+    }
+
+    /// Return the winning lines, each one as the list of the coordinates `(row, column)` of its cells
+    pub fn lines(&self) -> &[Vec<(usize, usize)>] {
+        &self.lines
+    }
+
+    /// Test if the cell at the given coordinate is part of a winning line.
+    pub fn contains(&self, p_x: usize, p_y: usize) -> bool {
+        self.lines.iter().any(|l_line| l_line.contains(&(p_x, p_y)))
+    }
+}
+
+/// A move played in a [`Game`], saved in its history to be undone and redone.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Move {
+    /// The row of the filled cell
+    row: usize,
+    /// The column of the filled cell
+    column: usize,
+    /// The player who filled the cell
+    player: common::Player,
+}
+
+impl Move {
+    /// Return the row of the filled cell
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Return the column of the filled cell
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// The reasons why a move can be refused, see [`Game::set_cell`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MoveError {
+    /// The cell is outside of the grid
+    OutOfBounds,
+    /// The cell is already filled
+    Occupied,
+    /// The game is already won or drawn
+    GameAlreadyOver,
+    /// The player is not the current player
+    NotYourTurn,
+    /// With the gravity, the cell below is still free
+    NotLowest,
+    /// With the gravity, every cell of the column is filled
+    ColumnFull,
+}
+
+/// Implementation of the [`fmt::Display`] trait for a [`MoveError`], to explain the error to the players
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::OutOfBounds => write!(f, "the cell is out of range"),
+            MoveError::Occupied => write!(f, "the cell is already taken"),
+            MoveError::GameAlreadyOver => write!(f, "the game is already over"),
+            MoveError::NotYourTurn => write!(f, "it is not your turn"),
+            MoveError::NotLowest => write!(f, "the cell is not the lowest free one of its column"),
+            MoveError::ColumnFull => write!(f, "the column is full"),
+        }
+    }
+}
+
+/// The reasons why a notation can not be read, see [`Game::from_notation`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotationError {
+    /// The notation does not have its four fields
+    MissingField,
+    /// The size is not `<rows>x<columns>`
+    InvalidSize(String),
+    /// The number of aligned symbols needed to win is not a number between 1 and the biggest dimension of the grid
+    InvalidWinLength(String),
+    /// The number of rows or the length of a row does not match the size
+    InvalidRows(String),
+    /// A cell is not `X`, `O` or `.`
+    InvalidCell(char),
+    /// The player who has to play is not `X` or `O`
+    InvalidPlayer(String),
+    /// A field after the fourth one is not `misere` or `gravity`
+    InvalidRules(String),
+}
+
+/// Implementation of the [`fmt::Display`] trait for a [`NotationError`], to explain the error to the players
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::MissingField => write!(
+                f,
+                "the notation must be `<rows>x<columns> <win length> <cells> <player> [misere] [gravity]`"
+            ),
+            NotationError::InvalidSize(l_field) => write!(f, "`{}` is not a grid size", l_field),
+            NotationError::InvalidWinLength(l_field) => {
+                write!(f, "`{}` is not a valid win length", l_field)
+            }
+            NotationError::InvalidRows(l_field) => {
+                write!(f, "the cells `{}` do not match the size of the grid", l_field)
+            }
+            NotationError::InvalidCell(l_cell) => write!(f, "`{}` is not a cell", l_cell),
+            NotationError::InvalidPlayer(l_field) => write!(f, "`{}` is not a player", l_field),
+            NotationError::InvalidRules(l_field) => {
+                write!(f, "`{}` is not misere or gravity", l_field)
+            }
+        }
+    }
+}
+
+/// What the player did during its turn, see [`player_turn`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TurnStatus {
+    /// The player has filled a cell
+    Played,
+    /// The player has undone, redone a move or loaded a game, the player who has to play may have changed
+    Changed,
+    /// The player wants to quit the game
+    Quit,
+}
+
 /// The structure saving all the elements necessary for the good functioning of a game.
 #[derive(Debug, Clone)]
 pub struct Game {
-    /// The game of the current game
-    grid: Vec<Vec<String>>,
+    /// The grid of the current game
+    grid: Vec<Vec<Cell>>,
+    /// The number of consecutive symbols needed to win
+    win_length: usize,
     /// The current player playing
     current_player: common::Player,
+    /// The moves played, in order
+    history: Vec<Move>,
+    /// The moves undone, the last one is the first to redo
+    undone: Vec<Move>,
+    /// If set, the player completing a line loses the game
+    misere: bool,
+    /// If set, only the lowest free cell of each column can be filled
+    gravity: bool,
 }
 
 impl Game {
-    /// Create a new game
-    pub fn new(p_size: usize) -> Self {
-        let mut l_grid = vec![vec![0.to_string(); p_size]; p_size];
+    /// Create a new game of `p_rows` rows and `p_columns` columns, won by the first player aligning `p_win_length`
+    /// symbols.
+    ///
+    /// Player one starts, see [`Game::with_first_player`] to choose another one.
+    pub fn new(p_rows: usize, p_columns: usize, p_win_length: usize) -> Self {
+        Game {
+            grid: vec![vec![Cell::Empty; p_columns]; p_rows],
+            win_length: p_win_length,
+            current_player: common::Player::PlayerOne,
+            history: Vec::new(),
+            undone: Vec::new(),
+            misere: false,
+            gravity: false,
+        }
+    }
+
+    /// Set the player playing the first move, to call before any move is played.
+    pub fn with_first_player(mut self, p_player: common::Player) -> Self {
+        self.current_player = p_player;
+        self
+    }
+
+    /// Play with the misère rules, or not: the player completing a line loses the game, see [`Game::outcome`].
+    pub fn with_misere(mut self, p_misere: bool) -> Self {
+        self.misere = p_misere;
+        self
+    }
+
+    /// Return `true` if the game is played with the misère rules, see [`Game::with_misere`].
+    pub fn is_misere(&self) -> bool {
+        self.misere
+    }
 
-        for i in 0..l_grid.len() {
-            for j in 0..l_grid[i].len() {
-                l_grid[i][j] = ((p_size * i) + j).to_string();
+    /// Play with the gravity, or not: the symbols fall to the bottom of the grid, only the lowest free cell of each
+    /// column can be filled, see [`Game::legal_moves`].
+    pub fn with_gravity(mut self, p_gravity: bool) -> Self {
+        self.gravity = p_gravity;
+        self
+    }
+
+    /// Return `true` if the game is played with the gravity, see [`Game::with_gravity`].
+    pub fn has_gravity(&self) -> bool {
+        self.gravity
+    }
+
+    /// Return the number of rows of the grid.
+    pub fn rows(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// Return the number of columns of the grid.
+    pub fn columns(&self) -> usize {
+        self.grid[0].len()
+    }
+
+    /// Return the number of consecutive symbols needed to win.
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    /// Return the moves played since the beginning of the game, in order.
+    pub fn moves(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Return the content of the cell at the given coordinate.
+    pub fn cell(&self, p_x: usize, p_y: usize) -> Cell {
+        self.grid[p_x][p_y]
+    }
+
+    /// Return the coordinates `(row, column)` of every free cell, row by row.
+    pub fn free_cells(&self) -> Vec<(usize, usize)> {
+        let mut l_cells = Vec::new();
+        for i in 0..self.rows() {
+            for j in 0..self.columns() {
+                if self.cell_is_free(i, j) {
+                    l_cells.push((i, j));
+                }
             }
         }
 
-        Game {
-            grid: l_grid,
-            current_player: common::Player::PlayerOne,
+        l_cells
+    }
+
+    /// Return the coordinates `(row, column)` of every cell which can be filled, row by row: the free cells, or with the
+    /// gravity the lowest free cell of each column.
+    pub fn legal_moves(&self) -> Vec<(usize, usize)> {
+        self.free_cells()
+            .into_iter()
+            .filter(|(l_x, l_y)| !self.gravity || !self.cell_below_is_free(*l_x, *l_y))
+            .collect()
+    }
+
+    /// Return the lowest free cell of the column, the one filled when playing in this column with the gravity, or
+    /// `None` if the column is full or does not exist.
+    pub fn lowest_free_cell(&self, p_column: usize) -> Option<(usize, usize)> {
+        if p_column >= self.columns() {
+            return None;
         }
+
+        (0..self.rows())
+            .rev()
+            .find(|l_row| self.cell_is_free(*l_row, p_column))
+            .map(|l_row| (l_row, p_column))
     }
 
-    /// Return the size of the grid.
-    pub fn len(&self) -> usize {
-        self.grid[0].len()
+    /// Fill the cell with the given player, if the game is not over, it is the turn of this player, and the cell exist and
+    /// is free. With the gravity, the cell must also be the lowest free cell of its column.
+    ///
+    /// The move is added to the history, and the moves undone can no longer be redone.
+    /// Return the [`MoveError`] explaining why the move is refused, if so.
+    pub fn set_cell(
+        &mut self,
+        p_x: usize,
+        p_y: usize,
+        p_player: common::Player,
+    ) -> Result<(), MoveError> {
+        if self.is_over() {
+            Err(MoveError::GameAlreadyOver)
+        } else if p_player != self.current_player {
+            Err(MoveError::NotYourTurn)
+        } else if p_x >= self.rows() || p_y >= self.columns() {
+            Err(MoveError::OutOfBounds)
+        } else if !self.cell_is_free(p_x, p_y) {
+            Err(MoveError::Occupied)
+        } else if self.gravity && self.cell_below_is_free(p_x, p_y) {
+            Err(MoveError::NotLowest)
+        } else {
+            self.grid[p_x][p_y] = Cell::Occupied(p_player);
+            self.history.push(Move {
+                row: p_x,
+                column: p_y,
+                player: p_player,
+            });
+            self.undone.clear();
+            Ok(())
+        }
     }
 
-    /// Set the value in the grid, if this cell exist and is free.
-    pub fn set_cell(&mut self, p_x: usize, p_y: usize, p_value: &String) -> bool {
-        DEBUG!("Row to change {}", p_x);
-        DEBUG!("Column to change {}", p_y);
+    /// Fill the cell with the current player, then give the turn to the other player, see [`Game::set_cell`].
+    pub fn play(&mut self, p_x: usize, p_y: usize) -> Result<(), MoveError> {
+        self.set_cell(p_x, p_y, self.current_player)?;
+        self.toggle_player();
+        Ok(())
+    }
 
-        if p_x < self.grid.len() && p_y < self.grid[0].len() {
-            if self.cell_is_free(p_x, p_y) {
-                self.grid[p_x][p_y] = p_value.to_string();
+    /// Cancel the last move of the history, the player who made it becomes the current player.
+    /// Return `false` if there is no move to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(l_move) => {
+                self.grid[l_move.row][l_move.column] = Cell::Empty;
+                self.current_player = l_move.player;
+                self.undone.push(l_move);
                 true
-            } else {
-                false
             }
-        } else {
-            false
+            None => false,
+        }
+    }
+
+    /// Play again the last undone move, the opponent of the player who made it becomes the current player.
+    /// Return `false` if there is no move to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(l_move) => {
+                self.grid[l_move.row][l_move.column] = Cell::Occupied(l_move.player);
+                self.current_player = l_move.player;
+                self.toggle_player();
+                self.history.push(l_move);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Write the position of the game in the notation described in the [module documentation](self).
+    pub fn to_notation(&self) -> String {
+        let l_cells: Vec<String> = self
+            .grid
+            .iter()
+            .map(|l_row| {
+                l_row
+                    .iter()
+                    .map(|l_cell| match l_cell {
+                        Cell::Empty => '.',
+                        Cell::Occupied(l_player) => player_letter(*l_player),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        format!(
+            "{}x{} {} {} {}{}{}",
+            self.rows(),
+            self.columns(),
+            self.win_length,
+            l_cells.join("/"),
+            player_letter(self.current_player),
+            if self.misere { " misere" } else { "" },
+            if self.gravity { " gravity" } else { "" }
+        )
+    }
+
+    /// Create a game from a position written in the notation described in the [module documentation](self).
+    ///
+    /// The history of the created game is empty.
+    pub fn from_notation(p_notation: &str) -> Result<Self, NotationError> {
+        let l_fields: Vec<&str> = p_notation.split_whitespace().collect();
+        if l_fields.len() < 4 {
+            return Err(NotationError::MissingField);
+        }
+
+        let (l_rows, l_columns) = parse_size(l_fields[0])
+            .filter(|(l_rows, l_columns)| *l_rows > 0 && *l_columns > 0)
+            .ok_or_else(|| NotationError::InvalidSize(l_fields[0].to_string()))?;
+
+        let l_win_length = l_fields[1]
+            .parse::<usize>()
+            .ok()
+            .filter(|l_value| *l_value > 0 && *l_value <= l_rows.max(l_columns))
+            .ok_or_else(|| NotationError::InvalidWinLength(l_fields[1].to_string()))?;
+
+        let mut l_game = Game::new(l_rows, l_columns, l_win_length);
+
+        let l_grid: Vec<&str> = l_fields[2].split('/').collect();
+        if l_grid.len() != l_rows
+            || l_grid
+                .iter()
+                .any(|l_row| l_row.chars().count() != l_columns)
+        {
+            return Err(NotationError::InvalidRows(l_fields[2].to_string()));
+        }
+        for (i, l_row) in l_grid.iter().enumerate() {
+            for (j, l_letter) in l_row.chars().enumerate() {
+                l_game.grid[i][j] = match l_letter {
+                    '.' => Cell::Empty,
+                    _ => Cell::Occupied(
+                        letter_player(l_letter).ok_or(NotationError::InvalidCell(l_letter))?,
+                    ),
+                };
+            }
+        }
+
+        let mut l_letters = l_fields[3].chars();
+        l_game.current_player = match (l_letters.next().and_then(letter_player), l_letters.next()) {
+            (Some(l_player), None) => l_player,
+            _ => return Err(NotationError::InvalidPlayer(l_fields[3].to_string())),
+        };
+
+        for l_rules in &l_fields[4..] {
+            match l_rules.to_lowercase().as_str() {
+                "misere" => l_game.misere = true,
+                "gravity" => l_game.gravity = true,
+                _ => return Err(NotationError::InvalidRules(l_rules.to_string())),
+            }
+        }
+
+        Ok(l_game)
+    }
+
+    /// Return a hash of the position: the content of the cells and the current player.
+    ///
+    /// Two different positions of the same grid have almost surely different hashes, it is used as key for the
+    /// positions already searched by the computer. Each pair (cell, player) is given its pseudo-random number, see
+    /// [`random::mix`], and the hash is the _xor_ of the numbers of the filled cells, computed again from the whole
+    /// grid at each call.
+    pub fn position_hash(&self) -> u64 {
+        let mut l_hash = match self.current_player {
+            common::Player::PlayerOne => 0,
+            common::Player::PlayerTwo => random::mix(u64::MAX),
+        };
+
+        for (l_index, l_cell) in self.grid.iter().flatten().enumerate() {
+            match l_cell {
+                Cell::Empty => {}
+                Cell::Occupied(common::Player::PlayerOne) => {
+                    l_hash ^= random::mix(2 * l_index as u64)
+                }
+                Cell::Occupied(common::Player::PlayerTwo) => {
+                    l_hash ^= random::mix((2 * l_index as u64) + 1)
+                }
+            }
         }
+
+        l_hash
     }
 
     /// Return the current player, see [`common::Player`]
@@ -111,128 +556,358 @@ impl Game {
 
     /// Change the current player to the other one.
     pub fn toggle_player(&mut self) {
-        self.current_player = match self.current_player {
-            common::Player::PlayerOne => common::Player::PlayerTwo,
-            common::Player::PlayerTwo => common::Player::PlayerOne,
-        }
+        self.current_player = self.current_player.opponent();
     }
 
-    /// Return the symbol to place in the grid of the current player, see [`common::PLAYER_ONE_SYMBOL`] and  [`common::PLAYER_TWO_SYMBOL`]
-    pub fn current_symbol(&self) -> &str {
-        match self.current_player {
-            common::Player::PlayerOne => common::PLAYER_ONE_SYMBOL,
-            common::Player::PlayerTwo => common::PLAYER_TWO_SYMBOL,
+    /// Return the [`GameOutcome`] of the game in its current state.
+    ///
+    /// With the misère rules, the game is won by the opponent of the player who completed a line.
+    pub fn outcome(&self) -> GameOutcome {
+        match find_winner(&self.grid, self.win_length) {
+            Some(l_player) if self.misere => GameOutcome::Won(l_player.opponent()),
+            Some(l_winner) => GameOutcome::Won(l_winner),
+            None => {
+                if self.is_full() {
+                    GameOutcome::Draw
+                } else {
+                    GameOutcome::InProgress
+                }
+            }
         }
     }
 
-    /// Test if there is a winner.
-    /// Return `true` is there is a winner, `false` otherwise.
+    /// Return the [`Victory`] if a player has completed a line, `None` otherwise.
+    ///
+    /// With the misère rules, this player has lost the game, see [`Game::outcome`].
+    pub fn victory(&self) -> Option<Victory> {
+        test_winner(&self.grid, self.win_length)
+    }
+
+    /// Test if the game is finished, by a win or a draw.
+    /// Return `true` is the game is over, `false` otherwise.
     pub fn is_over(&self) -> bool {
-        test_winner(&self.grid)
+        self.outcome() != GameOutcome::InProgress
+    }
+
+    /// Test if every cell of the grid is filled.
+    /// Return `true` if no cell is free, `false` otherwise
+    fn is_full(&self) -> bool {
+        self.grid
+            .iter()
+            .all(|l_row| l_row.iter().all(|l_cell| *l_cell != Cell::Empty))
+    }
+
+    /// Test if the cell under the one at the given coordinate exists and is free.
+    fn cell_below_is_free(&self, p_x: usize, p_y: usize) -> bool {
+        p_x + 1 < self.rows() && self.cell_is_free(p_x + 1, p_y)
     }
 
     /// Test id the cell at the given coordinate is free.
     /// Return `true` if the cell is free, `false` otherwise
     fn cell_is_free(&self, p_x: usize, p_y: usize) -> bool {
-        DEBUG!(
-            "Is already taken by opponent ? {}",
-            self.grid[p_x][p_y] == common::PLAYER_TWO_SYMBOL.to_string()
-        );
-        DEBUG!(
-            "Is already taken by player ? {}",
-            self.grid[p_x][p_y] == common::PLAYER_ONE_SYMBOL.to_string()
-        );
-
-        !(self.grid[p_x][p_y] == common::PLAYER_TWO_SYMBOL.to_string()
-            || self.grid[p_x][p_y] == common::PLAYER_ONE_SYMBOL.to_string())
+        self.grid[p_x][p_y] == Cell::Empty
     }
 }
 
-/// Implementation of the [`fmt::Display`] trait for a [`Game`] instance
-/// # Example
-/// ```rust
-/// let size_grid: usize = 3; // An integer greater than 2, and less than 10, preferably
-/// let mut game = Game::new(size_grid);
+/// Create a new [`Game`] associated to a [`screen::Screen`].
 ///
-/// println!(game);
-/// ```
-impl fmt::Display for Game {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut l_grid: String = format!("{}+\n", "+-----".repeat(self.grid.len()));
-        for i in 0..self.grid.len() {
-            l_grid.push_str(&format!("|"));
-            for j in 0..self.grid[i].len() {
-                l_grid.push_str(&format!("{:^5}|", self.grid[i][j]));
-            }
-            l_grid.push_str(&format!("\n{}+\n", "+-----".repeat(self.grid[i].len())));
+/// The player will be asked to enter the size of the grid `(rows, columns)` and the number of aligned symbols needed to
+/// win, if they are not given.
+pub fn create_game(
+    p_screen: &screen::Screen,
+    p_size: Option<(usize, usize)>,
+    p_win_length: Option<usize>,
+) -> Game {
+    let (l_rows, l_columns) = match p_size {
+        Some(l_size) => l_size,
+        None => ask_size(p_screen),
+    };
+    let l_win_length = match p_win_length {
+        Some(l_value) if l_value > 2 && l_value <= l_rows.max(l_columns) => l_value,
+        Some(l_value) => {
+            p_screen.send_msg(&format!(
+                "\x1B[41m{} aligned symbols can not be made on this grid\x1B[0m",
+                l_value
+            ));
+            ask_win_length(p_screen, l_rows, l_columns)
         }
+        None => ask_win_length(p_screen, l_rows, l_columns),
+    };
 
-        write!(f, "{}", l_grid)
-    }
+    Game::new(l_rows, l_columns, l_win_length)
 }
 
-/// Create a new [`Game`] associated to a [`screen::Screen`].
-///
-/// The player will be asked to enter the size of the grid.
-pub fn create_game(p_screen: &screen::Screen) -> Game {
-    p_screen.send_msg("\x1B[34mEnter the size of the grid you want (between 3 and 9):\x1B[0m ");
+/// Ask who starts the games, see [`common::FirstPlayer`].
+pub fn ask_first_player(p_screen: &screen::Screen) -> common::FirstPlayer {
+    p_screen.send_msg(
+        "\x1B[34mWho starts ? 1 for player one, 2 for player two, a to start in turn, r for a coin toss (empty to start in turn):\x1B[0m ",
+    );
 
-    let l_game_returned: Game;
+    loop {
+        match read_keyboard().trim().to_lowercase().as_str() {
+            "1" => return common::FirstPlayer::Fixed(common::Player::PlayerOne),
+            "2" => return common::FirstPlayer::Fixed(common::Player::PlayerTwo),
+            "" | "a" => return common::FirstPlayer::Alternate,
+            "r" => return common::FirstPlayer::Random,
+            _ => p_screen.send_msg("\x1B[41mBad entry, please enter 1, 2, a or r :\x1B[0m  "),
+        }
+    }
+}
+
+/// Ask who plays the given player: a human, the computer or a player of another program, see
+/// [`player::PlayerController`].
+pub fn ask_player(
+    p_screen: &screen::Screen,
+    p_player: common::Player,
+) -> Box<dyn player::PlayerController> {
+    let l_name = match p_player {
+        common::Player::PlayerOne => "player one (X)",
+        common::Player::PlayerTwo => "player two (O)",
+    };
+    p_screen.send_msg(&format!(
+        "\x1B[34mWho plays {} ? h for a human, c for the computer, m for the computer with random games, n for a player on the network (empty for a human):\x1B[0m ",
+        l_name
+    ));
 
     loop {
-        match read_keyboard().trim().parse::<usize>() {
-            Ok(l_value) => {
-                if l_value > 2 && l_value < 10 {
-                    l_game_returned = Game::new(l_value);
-                    break;
-                } else {
-                    p_screen.send_msg("\x1B[41mBad entry, please enter en number greater than 2 and lower than 9. Please retry :\x1B[0m  ");
-                }
+        match read_keyboard().trim().to_lowercase().as_str() {
+            "" | "h" => return Box::new(player::Human),
+            "c" => {
+                let l_level = ask_level(p_screen);
+                return Box::new(player::Computer::new(
+                    ask_computer_settings(p_screen).with_level(l_level),
+                ));
             }
-            Err(_) => {
-                p_screen.send_msg("\x1B[41mBad entry, please retry :\x1B[0m  ");
+            "m" => {
+                let l_level = ask_level(p_screen);
+                return Box::new(player::Computer::new(
+                    ask_monte_carlo_settings(p_screen).with_level(l_level),
+                ));
             }
+            "n" => return Box::new(ask_network_peer(p_screen)),
+            _ => p_screen.send_msg("\x1B[41mBad entry, please enter h, c, m or n :\x1B[0m  "),
         }
     }
+}
+
+/// Create a [`Game`] on a grid of the given size, `<size>` or `<rows>x<columns>` between 3 and 9, see [`parse_size`].
+///
+/// The number of aligned symbols needed to win is between 3 and the biggest dimension of the grid, by default the
+/// smallest dimension. Return the reason why the game can not be created, if any.
+pub fn new_game(p_size: &str, p_win_length: Option<usize>) -> Result<Game, String> {
+    let (l_rows, l_columns) = parse_size(p_size)
+        .filter(|(l_rows, l_columns)| (3..10).contains(l_rows) && (3..10).contains(l_columns))
+        .ok_or_else(|| format!("`{}` is not a grid size between 3 and 9", p_size.trim()))?;
+    let l_win_length = p_win_length.unwrap_or_else(|| l_rows.min(l_columns));
+
+    if l_win_length < 3 || l_win_length > l_rows.max(l_columns) {
+        return Err(format!(
+            "the number of aligned symbols must be between 3 and {}",
+            l_rows.max(l_columns)
+        ));
+    }
 
-    l_game_returned
+    Ok(Game::new(l_rows, l_columns, l_win_length))
+}
+
+/// Read a game saved in the file at the given path, see [`Game::from_notation`].
+///
+/// Return the loaded game, or the reason why it can not be loaded.
+pub fn load_game(p_path: &str) -> Result<Game, String> {
+    let l_content = fs::read_to_string(p_path)
+        .map_err(|l_error| format!("can not read {} ({})", p_path, l_error))?;
+
+    Game::from_notation(l_content.trim()).map_err(|l_error| l_error.to_string())
 }
 
 /// Plays the current player's turn of [`Game`] and displays the grid and information on the [`screen::Screen`].
 ///
-/// Return `true` if the player has modified the grid, `false` if the player has pressed the letter `q`.
+/// The player can enter a cell number, or a column number with the gravity, see [`drop_in_column`], or one of the
+/// commands:
+/// - `h` to get a hint, the move advised by the computer, see [`ai::hint`]
+/// - `u` to undo the last move and `r` to redo it
+/// - `save <file>` to save the game in a file, and `load <file>` to replace the game by the one saved in a file
+/// - `q` to quit the game
+///
+/// Return the [`TurnStatus`]: [`TurnStatus::Played`] if the player has modified the grid, [`TurnStatus::Changed`] if the
+/// player has undone, redone a move or loaded a game and [`TurnStatus::Quit`] if the player has pressed the letter `q`.
 /// As long as a valid entry has not been made, the player will be asked again to enter a value
-pub fn player_turn(p_screen: &screen::Screen, p_game: &mut Game) -> bool {
-
-    if p_game.current_player() == common::Player::PlayerOne {
-        p_screen.send_msg(
-            "\x1B[32mPlayer one it is your turn. Enter the cell you want to fill.\x1B[0m \x1B[41mq to quit the game\x1B[0m",
-        );
+pub fn player_turn(p_screen: &screen::Screen, p_game: &mut Game) -> TurnStatus {
+    let l_move = if p_game.has_gravity() {
+        "the column where you want to drop your symbol"
     } else {
-        p_screen.send_msg(
-            "\x1B[31mPlayer two it is your turn. Enter the cell you want to fill.\x1B[0m \x1B[41mq to quit the game\x1B[0m",
-        );
-    }
+        "the cell you want to fill"
+    };
+    announce_turn(p_screen, p_game.current_player(), l_move, COMMANDS_HELP);
 
     loop {
         let entered_key = read_keyboard();
-        if entered_key == "q" {
-            return false;
-        } else {
-            match entered_key.parse() {
-                Ok(l_cell) => {
-                    if change_cell(p_game, l_cell, &String::from(p_game.current_symbol())) {
-                        return true;
+        let (l_command, l_argument) = match entered_key.trim().split_once(' ') {
+            Some((l_command, l_argument)) => (l_command.to_lowercase(), l_argument.trim()),
+            None => (entered_key.trim().to_lowercase(), ""),
+        };
+
+        match l_command.as_str() {
+            "q" => return TurnStatus::Quit,
+            "h" => match ai::hint::hint(p_game) {
+                Some(l_hint) => {
+                    let (l_x, l_y) = l_hint.cell();
+                    if p_game.has_gravity() {
+                        p_screen.send_msg(&format!(
+                            "\x1B[44mHint: play in the column {}, {}\x1B[0m",
+                            l_y,
+                            l_hint.reason()
+                        ));
                     } else {
-                        p_screen.send_msg(
-                            "\x1B[41mBad entry, the cell is already taken or out of range\x1B[0m",
-                        );
+                        p_screen.send_msg(&format!(
+                            "\x1B[44mHint: fill the cell {}, {}\x1B[0m",
+                            (l_x * p_game.columns()) + l_y,
+                            l_hint.reason()
+                        ));
                     }
                 }
+                None => p_screen.send_msg("\x1B[41mThere is no move to advise\x1B[0m"),
+            },
+            "u" => {
+                if p_game.undo() {
+                    DEBUG!("[Game] Move undone");
+                    return TurnStatus::Changed;
+                } else {
+                    p_screen.send_msg("\x1B[41mThere is no move to undo\x1B[0m");
+                }
+            }
+            "r" => {
+                if p_game.redo() {
+                    DEBUG!("[Game] Move redone");
+                    return TurnStatus::Changed;
+                } else {
+                    p_screen.send_msg("\x1B[41mThere is no move to redo\x1B[0m");
+                }
+            }
+            "save" if !l_argument.is_empty() => {
+                match fs::write(l_argument, format!("{}\n", p_game.to_notation())) {
+                    Ok(()) => p_screen.send_msg(&format!("Game saved in {}", l_argument)),
+                    Err(l_error) => p_screen.send_msg(&format!(
+                        "\x1B[41mCan not save the game in {} ({})\x1B[0m",
+                        l_argument, l_error
+                    )),
+                }
+            }
+            "load" if !l_argument.is_empty() => match load_game(l_argument) {
+                Ok(l_game) => {
+                    *p_game = l_game;
+                    return TurnStatus::Changed;
+                }
+                Err(l_error) => p_screen.send_msg(&format!(
+                    "\x1B[41mCan not load the game, {}\x1B[0m",
+                    l_error
+                )),
+            },
+            _ => match l_command.parse() {
+                Ok(l_number) if p_game.has_gravity() => match drop_in_column(p_game, l_number) {
+                    Ok(()) => return TurnStatus::Played,
+                    Err(l_error) => {
+                        p_screen.send_msg(&format!("\x1B[41mBad entry, {}\x1B[0m", l_error));
+                    }
+                },
+                Ok(l_cell) => match change_cell(p_game, l_cell) {
+                    Ok(()) => return TurnStatus::Played,
+                    Err(l_error) => {
+                        p_screen.send_msg(&format!("\x1B[41mBad entry, {}\x1B[0m", l_error));
+                    }
+                },
                 Err(_) => {
                     p_screen.send_msg("\x1B[41mBad entry, please retry\x1B[0m");
                 }
+            },
+        }
+    }
+}
+
+/// Fill the cell of the given number, as shown on the [`screen::Screen`], with the current player.
+pub fn change_cell(p_game: &mut Game, p_cell: usize) -> Result<(), MoveError> {
+    let p_x: usize = p_cell / p_game.columns();
+    let p_y: usize = p_cell % p_game.columns();
+    DEBUG!("Row to change {}", p_x);
+    DEBUG!("Column to change {}", p_y);
+    let l_player = p_game.current_player();
+
+    p_game.set_cell(p_x, p_y, l_player)
+}
+
+/// Fill the lowest free cell of the column of the given number, as shown on the [`screen::Screen`], with the current
+/// player, for a game played with the gravity.
+pub fn drop_in_column(p_game: &mut Game, p_column: usize) -> Result<(), MoveError> {
+    let (l_x, l_y) = match p_game.lowest_free_cell(p_column) {
+        Some(l_cell) => l_cell,
+        None if p_column < p_game.columns() => return Err(MoveError::ColumnFull),
+        None => return Err(MoveError::OutOfBounds),
+    };
+    DEBUG!("[Game] Drop in the column {}, row {}", l_y, l_x);
+    let l_player = p_game.current_player();
+
+    p_game.set_cell(l_x, l_y, l_player)
+}
+
+/// Read a line entered by the user, without the end of line.
+pub fn read_keyboard() -> String {
+    let mut buf = String::new();
+    stdin()
+        .read_line(&mut buf)
+        .expect("\x1B[31mCouldn't read line\x1B[0m");
+    //The letter case is kept, as it matters for file names, the commands are compared in lowercase
+    buf.replace("\n", "").replace("\r", "")
+}
+
+/// Tell the player that it is its turn, to enter the given move or one of the given commands, the command `q` to quit
+/// the game being always available.
+pub fn announce_turn(
+    p_screen: &screen::Screen,
+    p_player: common::Player,
+    p_move: &str,
+    p_commands: &str,
+) {
+    let l_message = format!(
+        "it is your turn. Enter {}.\x1B[0m \x1B[44m{}\x1B[0m \x1B[41mq to quit the game\x1B[0m",
+        p_move, p_commands
+    );
+    match p_player {
+        common::Player::PlayerOne => {
+            p_screen.send_msg(&format!("\x1B[32mPlayer one {}", l_message))
+        }
+        common::Player::PlayerTwo => {
+            p_screen.send_msg(&format!("\x1B[31mPlayer two {}", l_message))
+        }
+    }
+}
+
+/// Read the entries of the current player until a move is played, for the variants where the player can only undo the
+/// last move with `u` or quit the game with `q`, see [`announce_turn`].
+///
+/// The entry, in lowercase, is given to `p_play`, returning `None` if it is not a move, or the result of the move.
+/// Return the [`TurnStatus`] of the turn.
+pub fn read_turn<G, E: fmt::Display>(
+    p_screen: &screen::Screen,
+    p_game: &mut G,
+    p_undo: fn(&mut G) -> bool,
+    p_play: fn(&mut G, &str) -> Option<Result<(), E>>,
+) -> TurnStatus {
+    loop {
+        let l_entry = read_keyboard().trim().to_lowercase();
+        match l_entry.as_str() {
+            "q" => return TurnStatus::Quit,
+            "u" if p_undo(p_game) => {
+                DEBUG!("[Game] Move undone");
+                return TurnStatus::Changed;
             }
+            "u" => p_screen.send_msg("\x1B[41mThere is no move to undo\x1B[0m"),
+            _ => match p_play(p_game, &l_entry) {
+                Some(Ok(())) => return TurnStatus::Played,
+                Some(Err(l_error)) => {
+                    p_screen.send_msg(&format!("\x1B[41mBad entry, {}\x1B[0m", l_error))
+                }
+                None => p_screen.send_msg("\x1B[41mBad entry, please retry\x1B[0m"),
+            },
         }
     }
 }
@@ -243,85 +918,620 @@ pub fn player_turn(p_screen: &screen::Screen, p_game: &mut Game) -> bool {
 //
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 
-fn test_winner(p_grid: &Vec<Vec<String>>) -> bool {
-    let mut l_counter_diagonal_lru: i16 = 0; // Left-to-Right upward Diagonal
-    let mut l_counter_diagonal_lrd: i16 = 0; // Left-to-Right downward Diagonal
-    let score_to_win: i16 = p_grid.len() as i16;
+/// The commands available during the turn of a player, see [`player_turn`]
+const COMMANDS_HELP: &str = "h for a hint, u to undo, r to redo, save/load <file>";
 
-    for i in 0..p_grid.len() {
-        let mut l_counter_row: i16 = 0;
-        let mut l_counter_column: i16 = 0;
+/// The directions in which a line can be made: row, column, downward diagonal and upward diagonal.
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Return the [`Victory`] of the first player owning `p_win_length` consecutive cells anywhere in the grid, if any.
+///
+/// Every line of this player at least `p_win_length` long is reported, in its full length.
+fn test_winner(p_grid: &[Vec<Cell>], p_win_length: usize) -> Option<Victory> {
+    let mut l_victory: Option<Victory> = None;
 
+    for i in 0..p_grid.len() {
         for j in 0..p_grid[i].len() {
-            // Row
-            if p_grid[i][j] == common::PLAYER_TWO_SYMBOL {
-                l_counter_row -= 1;
-            } else if p_grid[i][j] == common::PLAYER_ONE_SYMBOL {
-                l_counter_row += 1;
+            if let Cell::Occupied(l_player) = p_grid[i][j] {
+                for l_direction in DIRECTIONS.iter() {
+                    // Only start from the first cell of a line
+                    let l_previous = (i as isize - l_direction.0, j as isize - l_direction.1);
+                    if cell_at(p_grid, l_previous.0, l_previous.1) == Some(p_grid[i][j]) {
+                        continue;
+                    }
+
+                    let l_line = aligned_cells(p_grid, i, j, *l_direction);
+                    if l_line.len() >= p_win_length {
+                        DEBUG!("[Game] Line of {:?} : {:?}", l_player, l_line);
+
+                        match l_victory.as_mut() {
+                            None => {
+                                l_victory = Some(Victory {
+                                    player: l_player,
+                                    lines: vec![l_line],
+                                })
+                            }
+                            Some(l_found) if l_found.player == l_player => {
+                                l_found.lines.push(l_line)
+                            }
+                            Some(_) => {}
+                        }
+                    }
+                }
             }
+        }
+    }
 
-            // Column
-            if p_grid[j][i] == common::PLAYER_TWO_SYMBOL {
-                l_counter_column -= 1;
-            } else if p_grid[j][i] == common::PLAYER_ONE_SYMBOL {
-                l_counter_column += 1;
+    l_victory
+}
+
+/// Return the first player owning `p_win_length` consecutive cells anywhere in the grid, if any.
+///
+/// It is the same test as [`test_winner`], without building the lines, to be fast enough for the search of the moves of
+/// the computer.
+fn find_winner(p_grid: &[Vec<Cell>], p_win_length: usize) -> Option<common::Player> {
+    for i in 0..p_grid.len() {
+        for j in 0..p_grid[i].len() {
+            if let Cell::Occupied(l_player) = p_grid[i][j] {
+                for l_direction in DIRECTIONS.iter() {
+                    let mut l_count: usize = 1;
+                    let (mut l_x, mut l_y) =
+                        (i as isize + l_direction.0, j as isize + l_direction.1);
+                    while l_count < p_win_length && cell_at(p_grid, l_x, l_y) == Some(p_grid[i][j])
+                    {
+                        l_count += 1;
+                        l_x += l_direction.0;
+                        l_y += l_direction.1;
+                    }
+
+                    if l_count >= p_win_length {
+                        return Some(l_player);
+                    }
+                }
             }
         }
+    }
 
-        DEBUG!("[Game] Score row {}", l_counter_row);
-        DEBUG!("[Game] Score column {}", l_counter_column);
+    None
+}
 
-        if l_counter_row >= score_to_win
-            || l_counter_row <= -score_to_win
-            || l_counter_column >= score_to_win
-            || l_counter_column <= -score_to_win
-        {
-            return true;
+/// Return the coordinates of the cells owned by the same player as the cell (`p_x`, `p_y`), from this one and going in
+/// the given direction.
+fn aligned_cells(
+    p_grid: &[Vec<Cell>],
+    p_x: usize,
+    p_y: usize,
+    p_direction: (isize, isize),
+) -> Vec<(usize, usize)> {
+    let l_cell = p_grid[p_x][p_y];
+    let mut l_line: Vec<(usize, usize)> = Vec::new();
+    let (mut l_x, mut l_y) = (p_x as isize, p_y as isize);
+
+    while cell_at(p_grid, l_x, l_y) == Some(l_cell) {
+        l_line.push((l_x as usize, l_y as usize));
+        l_x += p_direction.0;
+        l_y += p_direction.1;
+    }
+
+    l_line
+}
+
+/// Return the cell at the given coordinate, or `None` if it is outside of the grid.
+fn cell_at(p_grid: &[Vec<Cell>], p_x: isize, p_y: isize) -> Option<Cell> {
+    if p_x < 0 || p_y < 0 {
+        return None;
+    }
+
+    p_grid
+        .get(p_x as usize)
+        .and_then(|l_row| l_row.get(p_y as usize))
+        .copied()
+}
+
+/// Ask the level of the computer, see [`ai::Level`].
+fn ask_level(p_screen: &screen::Screen) -> ai::Level {
+    p_screen.send_msg(
+        "\x1B[34mChoose the level of the computer: e for easy, m for medium, h for hard, p for perfect (empty for perfect):\x1B[0m ",
+    );
+
+    loop {
+        match read_keyboard().trim().to_lowercase().as_str() {
+            "e" => return ai::Level::Easy,
+            "m" => return ai::Level::Medium,
+            "h" => return ai::Level::Hard,
+            "" | "p" => return ai::Level::Perfect,
+            _ => p_screen.send_msg("\x1B[41mBad entry, please enter e, m, h or p :\x1B[0m  "),
+        }
+    }
+}
+
+/// Ask the budget the computer can spend for each move, see [`alpha_beta::Budget`].
+fn ask_computer_settings(p_screen: &screen::Screen) -> ai::Settings {
+    p_screen.send_msg(
+        "\x1B[34mEnter the time (`2s`, `500ms`) or the number of positions (`100000`) the computer can search for each move (empty for 1s):\x1B[0m ",
+    );
+
+    loop {
+        let l_entry = read_keyboard();
+        if l_entry.trim().is_empty() {
+            return ai::Settings::default();
         }
 
-        // Left-to-Right upward Diagonal
-        if p_grid[i][i] == common::PLAYER_TWO_SYMBOL {
-            l_counter_diagonal_lru -= 1;
-        } else if p_grid[i][i] == common::PLAYER_ONE_SYMBOL {
-            l_counter_diagonal_lru += 1;
+        match l_entry.parse::<alpha_beta::Budget>() {
+            Ok(l_budget) => return ai::Settings::new(ai::Engine::AlphaBeta(l_budget)),
+            Err(l_error) => p_screen.send_msg(&format!(
+                "\x1B[41mBad entry, {}. Please retry :\x1B[0m  ",
+                l_error
+            )),
         }
+    }
+}
+
+/// Ask the number of random games the computer plays for each move, see [`ai::Engine::MonteCarlo`].
+fn ask_monte_carlo_settings(p_screen: &screen::Screen) -> ai::Settings {
+    p_screen.send_msg(&format!(
+        "\x1B[34mEnter the number of random games the computer can play for each move (empty for {}):\x1B[0m ",
+        ai::DEFAULT_ITERATIONS
+    ));
+
+    loop {
+        let l_entry = read_keyboard();
+        let l_iterations = if l_entry.trim().is_empty() {
+            Ok(ai::DEFAULT_ITERATIONS)
+        } else {
+            l_entry.trim().parse::<u32>()
+        };
 
-        // Check Left-to-Right upward Diagonal
-        if p_grid[i][(p_grid[i].len() - 1) - i] == common::PLAYER_TWO_SYMBOL {
-            l_counter_diagonal_lrd -= 1;
-        } else if p_grid[i][(p_grid[i].len() - 1) - i] == common::PLAYER_ONE_SYMBOL {
-            l_counter_diagonal_lrd += 1;
+        match l_iterations {
+            Ok(l_iterations) if l_iterations > 0 => {
+                return ai::Settings::new(ai::Engine::MonteCarlo(l_iterations))
+            }
+            _ => p_screen.send_msg("\x1B[41mBad entry, please enter a positive number :\x1B[0m  "),
         }
     }
+}
 
-    DEBUG!(
-        "[Game] Score left-to-tight upward diagonal {}",
-        l_counter_diagonal_lru
+/// Ask the address of the other program to connect to, or the port to wait for it on, see [`player::NetworkPeer`].
+fn ask_network_peer(p_screen: &screen::Screen) -> player::NetworkPeer {
+    p_screen.send_msg(
+        "\x1B[34mEnter the address of the other player to connect to (`<host>:<port>`), or the port to wait for the other player on:\x1B[0m ",
     );
-    DEBUG!(
-        "[Game] Score left-to-right downward diagonal {}",
-        l_counter_diagonal_lrd
+
+    loop {
+        let l_entry = read_keyboard();
+        let l_peer = match l_entry.trim().parse::<u16>() {
+            Ok(l_port) => {
+                p_screen.send_msg(&format!(
+                    "Waiting for the other player on the port {}...",
+                    l_port
+                ));
+                player::NetworkPeer::wait_on(l_port)
+            }
+            Err(_) => player::NetworkPeer::connect(l_entry.trim()),
+        };
+
+        match l_peer {
+            Ok(l_peer) => return l_peer,
+            Err(l_error) => p_screen.send_msg(&format!(
+                "\x1B[41mBad entry, {}. Please retry :\x1B[0m  ",
+                l_error
+            )),
+        }
+    }
+}
+
+/// Ask the size of the grid, between 3 and 9, either `<size>` or `<rows>x<columns>`.
+///
+/// Return the number of rows and columns.
+fn ask_size(p_screen: &screen::Screen) -> (usize, usize) {
+    p_screen.send_msg(
+        "\x1B[34mEnter the size of the grid you want (between 3 and 9), either `<size>` or `<rows>x<columns>`:\x1B[0m ",
     );
 
-    return l_counter_diagonal_lru >= score_to_win
-        || l_counter_diagonal_lru <= -score_to_win
-        || l_counter_diagonal_lrd >= score_to_win
-        || l_counter_diagonal_lrd <= -score_to_win;
+    let (l_rows, l_columns): (usize, usize);
+
+    loop {
+        match parse_size(&read_keyboard()) {
+            Some((l_entered_rows, l_entered_columns)) => {
+                if (3..10).contains(&l_entered_rows) && (3..10).contains(&l_entered_columns) {
+                    l_rows = l_entered_rows;
+                    l_columns = l_entered_columns;
+                    break;
+                } else {
+                    p_screen.send_msg("\x1B[41mBad entry, please enter en number greater than 2 and lower than 9. Please retry :\x1B[0m  ");
+                }
+            }
+            None => {
+                p_screen.send_msg("\x1B[41mBad entry, please retry :\x1B[0m  ");
+            }
+        }
+    }
+
+    (l_rows, l_columns)
 }
 
-fn change_cell(p_game: &mut Game, p_cell: u8, p_value: &String) -> bool {
-    let p_x: usize = (p_cell as usize) / p_game.len();
-    let p_y: usize = (p_cell as usize) % p_game.len();
+/// Parse a grid size entered as `<size>` for a square grid, or as `<rows>x<columns>`.
+///
+/// Return the number of rows and columns, or `None` if the entry is not a size.
+fn parse_size(p_entry: &str) -> Option<(usize, usize)> {
+    let l_entry = p_entry.trim().to_lowercase();
 
-    p_game.set_cell(p_x, p_y, p_value)
+    match l_entry.split_once('x') {
+        Some((l_rows, l_columns)) => Some((
+            l_rows.trim().parse::<usize>().ok()?,
+            l_columns.trim().parse::<usize>().ok()?,
+        )),
+        None => {
+            let l_size = l_entry.parse::<usize>().ok()?;
+            Some((l_size, l_size))
+        }
+    }
 }
 
-fn read_keyboard() -> String {
-    let mut buf = String::new();
-    stdin()
-        .read_line(&mut buf)
-        .expect("\x1B[31mCouldn't read line\x1B[0m");
-    //To don't care about the letter case, every thing is in lowercase
-    buf.to_lowercase();
-    buf.replace("\n", "").replace("\r", "")
+/// Ask the number of aligned symbols needed to win, between 3 and the biggest dimension of the grid.
+///
+/// An empty entry means the length of the smallest dimension, a full line on a square grid.
+fn ask_win_length(p_screen: &screen::Screen, p_rows: usize, p_columns: usize) -> usize {
+    let l_max = p_rows.max(p_columns);
+    let l_default = p_rows.min(p_columns);
+
+    if l_max == 3 {
+        return l_max;
+    }
+
+    p_screen.send_msg(&format!(
+        "\x1B[34mEnter the number of aligned symbols needed to win (between 3 and {}, empty for {}):\x1B[0m ",
+        l_max, l_default
+    ));
+
+    loop {
+        let l_entry = read_keyboard();
+        if l_entry.trim().is_empty() {
+            return l_default;
+        }
+
+        match l_entry.trim().parse::<usize>() {
+            Ok(l_value) if l_value > 2 && l_value <= l_max => return l_value,
+            _ => p_screen.send_msg(&format!(
+                "\x1B[41mBad entry, please enter a number between 3 and {}. Please retry :\x1B[0m  ",
+                l_max
+            )),
+        }
+    }
+}
+
+/// Return the letter of the given player in the notation, see [`Game::to_notation`]
+fn player_letter(p_player: common::Player) -> char {
+    match p_player {
+        common::Player::PlayerOne => 'X',
+        common::Player::PlayerTwo => 'O',
+    }
+}
+
+/// Return the player of the given letter in the notation, see [`Game::from_notation`]
+fn letter_player(p_letter: char) -> Option<common::Player> {
+    match p_letter {
+        'x' | 'X' => Some(common::Player::PlayerOne),
+        'o' | 'O' => Some(common::Player::PlayerTwo),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The coordinates `(row, column)` of the cells of each winning line, see [`Victory::lines`]
+    type Lines = Vec<Vec<(usize, usize)>>;
+
+    /// Return the grid written row by row from the top, the rows being separated by `/`: `X` for player one, `O` for
+    /// player two and `.` for an empty cell
+    fn grid(p_cells: &str) -> Vec<Vec<Cell>> {
+        p_cells
+            .split('/')
+            .map(|l_row| {
+                l_row
+                    .chars()
+                    .map(|l_letter| match l_letter {
+                        'X' => Cell::Occupied(common::Player::PlayerOne),
+                        'O' => Cell::Occupied(common::Player::PlayerTwo),
+                        _ => Cell::Empty,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Return the winner found by [`test_winner`] and its lines, checking that [`find_winner`] finds the same winner
+    fn winner(p_cells: &str, p_win_length: usize) -> Option<(common::Player, Lines)> {
+        let l_grid = grid(p_cells);
+        let l_victory = test_winner(&l_grid, p_win_length);
+
+        assert_eq!(
+            find_winner(&l_grid, p_win_length),
+            l_victory.as_ref().map(|l_victory| l_victory.player())
+        );
+        l_victory.map(|l_victory| (l_victory.player(), l_victory.lines().to_vec()))
+    }
+
+    #[test]
+    fn set_cell_fills_free_cells() {
+        let mut l_game = Game::new(3, 3, 3);
+        assert_eq!(l_game.cell(1, 2), Cell::Empty);
+
+        assert!(l_game.set_cell(1, 2, common::Player::PlayerOne).is_ok());
+        assert_eq!(l_game.cell(1, 2), Cell::Occupied(common::Player::PlayerOne));
+        assert!(l_game.set_cell(1, 2, common::Player::PlayerOne).is_err());
+    }
+
+    #[test]
+    fn draw_and_in_progress() {
+        let mut l_game = Game::new(3, 3, 3);
+        assert_eq!(l_game.outcome(), GameOutcome::InProgress);
+
+        l_game.grid = grid("XOX/XOO/OX.");
+        assert_eq!(l_game.outcome(), GameOutcome::InProgress);
+        assert!(!l_game.is_over());
+
+        l_game.grid = grid("XOX/XOO/OXX");
+        assert_eq!(l_game.outcome(), GameOutcome::Draw);
+        assert!(l_game.is_over());
+    }
+
+    #[test]
+    fn winner_row_and_column() {
+        assert_eq!(
+            winner(".../XXX/O.O", 3),
+            Some((
+                common::Player::PlayerOne,
+                vec![vec![(1, 0), (1, 1), (1, 2)]]
+            ))
+        );
+        assert_eq!(
+            winner("XO./XO./.OX", 3),
+            Some((
+                common::Player::PlayerTwo,
+                vec![vec![(0, 1), (1, 1), (2, 1)]]
+            ))
+        );
+    }
+
+    #[test]
+    fn winner_diagonals() {
+        assert_eq!(
+            winner("X.O/.XO/..X", 3),
+            Some((
+                common::Player::PlayerOne,
+                vec![vec![(0, 0), (1, 1), (2, 2)]]
+            ))
+        );
+        assert_eq!(
+            winner("X.O/XO./O.X", 3),
+            Some((
+                common::Player::PlayerTwo,
+                vec![vec![(0, 2), (1, 1), (2, 0)]]
+            ))
+        );
+    }
+
+    #[test]
+    fn winner_off_the_main_diagonals() {
+        assert_eq!(
+            winner("...../..X../...X./....X/O.O.O", 3),
+            Some((
+                common::Player::PlayerOne,
+                vec![vec![(1, 2), (2, 3), (3, 4)]]
+            ))
+        );
+        assert_eq!(
+            winner("..../..O./.O../O..X", 3),
+            Some((
+                common::Player::PlayerTwo,
+                vec![vec![(1, 2), (2, 1), (3, 0)]]
+            ))
+        );
+    }
+
+    #[test]
+    fn winner_rectangular_grid() {
+        assert_eq!(
+            winner("...../.XXXX/OOO..", 4),
+            Some((
+                common::Player::PlayerOne,
+                vec![vec![(1, 1), (1, 2), (1, 3), (1, 4)]]
+            ))
+        );
+        assert_eq!(
+            winner("X../O../O.X/O../O.X", 4),
+            Some((
+                common::Player::PlayerTwo,
+                vec![vec![(1, 0), (2, 0), (3, 0), (4, 0)]]
+            ))
+        );
+        assert_eq!(winner("XXX../OOO../.....", 4), None);
+    }
+
+    #[test]
+    fn winner_two_lines() {
+        assert_eq!(
+            winner("XXX/XOO/XO.", 3),
+            Some((
+                common::Player::PlayerOne,
+                vec![vec![(0, 0), (0, 1), (0, 2)], vec![(0, 0), (1, 0), (2, 0)]]
+            ))
+        );
+    }
+
+    #[test]
+    fn grid_sizes() {
+        assert_eq!(parse_size("4"), Some((4, 4)));
+        assert_eq!(parse_size(" 3X5 "), Some((3, 5)));
+        assert_eq!(parse_size("3x"), None);
+        assert_eq!(parse_size("three"), None);
+    }
+
+    #[test]
+    fn no_winner() {
+        assert_eq!(winner("XOX/XOO/OXX", 3), None);
+        assert_eq!(winner(".../.../...", 3), None);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut l_game = Game::new(3, 3, 3);
+        assert!(!l_game.undo());
+        l_game.play(0, 0).unwrap();
+        l_game.play(1, 1).unwrap();
+        let l_moves = l_game.moves().to_vec();
+
+        assert!(l_game.undo());
+        assert_eq!(l_game.moves(), &l_moves[..1]);
+        assert_eq!(l_game.current_player(), common::Player::PlayerTwo);
+        assert!(l_game.cell_is_free(1, 1));
+
+        assert!(l_game.undo());
+        assert!(l_game.moves().is_empty());
+        assert_eq!(l_game.current_player(), common::Player::PlayerOne);
+
+        assert!(l_game.redo());
+        assert!(l_game.redo());
+        assert!(!l_game.redo());
+        assert_eq!(l_game.moves(), &l_moves[..]);
+        assert_eq!(l_game.current_player(), common::Player::PlayerOne);
+        assert!(!l_game.cell_is_free(1, 1));
+    }
+
+    #[test]
+    fn new_move_clears_redo() {
+        let mut l_game = Game::new(3, 3, 3);
+        l_game.play(0, 0).unwrap();
+        l_game.play(1, 1).unwrap();
+        l_game.undo();
+
+        l_game.play(2, 2).unwrap();
+        assert!(!l_game.redo());
+        assert_eq!(l_game.to_notation(), "3x3 3 X../.../..O X");
+    }
+
+    #[test]
+    fn move_errors() {
+        let mut l_game = Game::new(3, 3, 3);
+        l_game.play(1, 1).unwrap();
+
+        assert_eq!(l_game.play(1, 1), Err(MoveError::Occupied));
+        assert_eq!(l_game.play(3, 0), Err(MoveError::OutOfBounds));
+        assert_eq!(
+            l_game.set_cell(0, 0, common::Player::PlayerOne),
+            Err(MoveError::NotYourTurn)
+        );
+
+        l_game.grid = grid("XXX/OO./...");
+        assert_eq!(l_game.play(2, 2), Err(MoveError::GameAlreadyOver));
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        let mut l_game = Game::new(3, 4, 3).with_misere(true).with_gravity(true);
+        l_game.play(2, 1).unwrap();
+        l_game.play(2, 2).unwrap();
+        l_game.play(1, 1).unwrap();
+
+        let l_notation = l_game.to_notation();
+        assert_eq!(l_notation, "3x4 3 ..../.X../.XO. O misere gravity");
+
+        let l_loaded = Game::from_notation(&l_notation).unwrap();
+        assert_eq!(l_loaded.to_notation(), l_notation);
+        assert_eq!(l_loaded.current_player(), common::Player::PlayerTwo);
+        assert!(l_loaded.is_misere() && l_loaded.has_gravity());
+        assert!(l_loaded.moves().is_empty());
+    }
+
+    #[test]
+    fn notation_errors() {
+        assert_eq!(
+            Game::from_notation("3x3 3 ...").err(),
+            Some(NotationError::MissingField)
+        );
+        assert_eq!(
+            Game::from_notation("3x3 3 .../... X").err(),
+            Some(NotationError::InvalidRows(String::from(".../...")))
+        );
+        assert_eq!(
+            Game::from_notation("3x3 3 .../.A./... X").err(),
+            Some(NotationError::InvalidCell('A'))
+        );
+        assert_eq!(
+            Game::from_notation("3x3 3 .../.../... Z").err(),
+            Some(NotationError::InvalidPlayer(String::from("Z")))
+        );
+        assert_eq!(
+            Game::from_notation("3x3 3 .../.../... X gravity bogus").err(),
+            Some(NotationError::InvalidRules(String::from("bogus")))
+        );
+    }
+
+    #[test]
+    fn same_position_same_hash() {
+        let mut l_first = Game::new(3, 3, 3);
+        let mut l_second = Game::new(3, 3, 3);
+        for (l_x, l_y) in &[(0, 0), (1, 1), (2, 2)] {
+            l_first.play(*l_x, *l_y).unwrap();
+        }
+        for (l_x, l_y) in &[(2, 2), (1, 1), (0, 0)] {
+            l_second.play(*l_x, *l_y).unwrap();
+        }
+        assert_eq!(l_first.position_hash(), l_second.position_hash());
+
+        l_second.toggle_player();
+        assert_ne!(l_first.position_hash(), l_second.position_hash());
+
+        l_first.undo();
+        assert_ne!(l_first.position_hash(), l_second.position_hash());
+    }
+
+    #[test]
+    fn misere_outcome() {
+        let mut l_game = Game::new(3, 3, 3);
+        l_game.grid = grid("XXX/OO./...");
+        assert_eq!(
+            l_game.outcome(),
+            GameOutcome::Won(common::Player::PlayerOne)
+        );
+
+        let l_game = l_game.with_misere(true);
+        assert_eq!(
+            l_game.outcome(),
+            GameOutcome::Won(common::Player::PlayerTwo)
+        );
+        assert_eq!(
+            l_game.victory().map(|l_victory| l_victory.player()),
+            Some(common::Player::PlayerOne)
+        );
+    }
+
+    #[test]
+    fn lowest_free_cell() {
+        let mut l_game = Game::new(3, 3, 3).with_gravity(true);
+        assert_eq!(l_game.lowest_free_cell(1), Some((2, 1)));
+
+        l_game.play(2, 1).unwrap();
+        assert_eq!(l_game.lowest_free_cell(1), Some((1, 1)));
+        assert_eq!(l_game.lowest_free_cell(0), Some((2, 0)));
+        assert_eq!(l_game.lowest_free_cell(3), None);
+    }
+
+    #[test]
+    fn gravity_moves() {
+        let mut l_game = Game::new(3, 3, 3).with_gravity(true);
+        assert_eq!(l_game.play(0, 0), Err(MoveError::NotLowest));
+
+        for _ in 0..3 {
+            drop_in_column(&mut l_game, 2).unwrap();
+            l_game.toggle_player();
+        }
+        assert_eq!(drop_in_column(&mut l_game, 2), Err(MoveError::ColumnFull));
+        assert_eq!(drop_in_column(&mut l_game, 3), Err(MoveError::OutOfBounds));
+        assert_eq!(l_game.play(1, 0), Err(MoveError::NotLowest));
+        assert_eq!(l_game.play(2, 0), Ok(()));
+    }
 }
@@ -1,20 +1,411 @@
 #![doc = include_str!("..//README.md")]
 
+mod ai;
+mod analysis;
+mod arena;
 mod common;
 mod game;
+mod player;
+mod qubic;
+mod record;
 mod screen;
 mod state_machine;
 mod tools;
+mod ultimate;
+
+use std::env;
+use std::iter::Peekable;
+
+/// The arguments of the program
+const USAGE: &str = "tic-tac-toe [--load <file>] [--size <rows>x<columns>] [--win <length>] [--misere] [--gravity] [--player-one <player>] [--player-two <player>] [--first <1|2|alternate|random>] [--best-of <games>] [--script-one <file>] [--script-two <file>] [--color <on|off>] [--trace <level>] [--variant <classic|ultimate|qubic>] [--replay <file> | --analyse [<file>] [--budget <budget>] | --arena <games>]";
 
 fn main() {
+    // The options are read first, they set the level of the traces
+    let l_options = match read_options(&mut env::args().skip(1).peekable()) {
+        Ok(l_options) => l_options,
+        Err(l_error) => {
+            screen::print(&format!("\x1B[41m{}\x1B[0m", l_error));
+            return;
+        }
+    };
+
     INFO!("[Main] Program Started");
 
-    let game_state_machine = state_machine::StateMachine::new_and_start();
-    game_state_machine.start_game();
+    match l_options.mode {
+        Mode::Play => match l_options.variant.unwrap_or_else(ask_variant) {
+            common::Variant::Classic => {
+                let game_state_machine =
+                    state_machine::StateMachine::new_and_start(l_options.setup);
+                game_state_machine.start_game();
 
-    INFO!("[Main] Game started");
+                INFO!("[Main] Game started");
 
-    game_state_machine.wait_end_game();
+                game_state_machine.wait_end_game();
+            }
+            common::Variant::Ultimate => ultimate::run(),
+            common::Variant::Qubic => qubic::run(l_options.cube_size),
+        },
+        Mode::Replay(l_path) => match record::load_record(&l_path) {
+            Ok(l_record) => {
+                let l_screen = screen::Screen::new_and_start();
+                record::replay(&l_screen, &l_record);
+                l_screen.stop_and_free();
+            }
+            Err(l_error) => screen::print(&format!(
+                "\x1B[41mCan not replay the game, {}\x1B[0m",
+                l_error
+            )),
+        },
+        Mode::Analyse(l_path) => {
+            let l_screen = screen::Screen::new_and_start();
+            let l_game = match l_path {
+                Some(l_path) => match game::load_game(&l_path) {
+                    Ok(l_game) => Some(l_game),
+                    Err(l_error) => {
+                        l_screen.send_msg(&format!(
+                            "\x1B[41mCan not load the game, {}\x1B[0m",
+                            l_error
+                        ));
+                        None
+                    }
+                },
+                None => analysis::ask_position(&l_screen),
+            };
+            if let Some(l_game) = l_game {
+                analysis::analyse(
+                    &l_screen,
+                    &l_game,
+                    l_options.budget.unwrap_or(analysis::DEFAULT_BUDGET),
+                );
+            }
+            l_screen.stop_and_free();
+        }
+        Mode::Arena(l_games) => {
+            let l_size = l_options.size.as_deref().unwrap_or("3");
+            match (
+                game::new_game(l_size, l_options.win_length).map(|l_game| {
+                    l_game
+                        .with_misere(l_options.misere)
+                        .with_gravity(l_options.gravity)
+                }),
+                l_options.engines,
+            ) {
+                (Ok(l_game), [Some(l_engine_one), Some(l_engine_two)]) => {
+                    arena::run(&l_game, &[l_engine_one, l_engine_two], l_games);
+                }
+                (Err(l_error), _) => screen::print(&format!(
+                    "\x1B[41mCan not create the game, {}\x1B[0m",
+                    l_error
+                )),
+                (Ok(_), _) => screen::print("\x1B[41mThe arena is played by two computers\x1B[0m"),
+            }
+        }
+    }
 
     INFO!("[Main] Program finished");
 }
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// What the program does
+enum Mode {
+    /// Play games, see [`state_machine`]
+    Play,
+    /// Replay the record saved in the given file, see [`record::replay`]
+    Replay(String),
+    /// Analyse the position saved in the given file, or typed in, see [`analysis`]
+    Analyse(Option<String>),
+    /// Play the given number of games between two computers, see [`arena`]
+    Arena(u32),
+}
+
+/// The options given in the arguments of the program
+struct Options {
+    /// What the program does
+    mode: Mode,
+    /// The elements of the games given in the arguments, the others are asked to the players
+    setup: state_machine::Setup,
+    /// The size of the grid, `<size>` or `<rows>x<columns>`
+    size: Option<String>,
+    /// The size of the cube of the qubic, read from the size of the grid
+    cube_size: Option<usize>,
+    /// The number of aligned symbols needed to win
+    win_length: Option<usize>,
+    /// The settings of the computers of the arena, `None` for a human
+    engines: [Option<ai::Settings>; 2],
+    /// If set, the player completing a line loses, see [`game::Game::with_misere`]
+    misere: bool,
+    /// If set, the symbols fall to the bottom of the grid, see [`game::Game::with_gravity`]
+    gravity: bool,
+    /// The game played, otherwise it is asked, see [`ask_variant`]
+    variant: Option<common::Variant>,
+    /// The budget of the search of each move of the analysis, see [`analysis::analyse`]
+    budget: Option<ai::alpha_beta::Budget>,
+}
+
+/// Read the options given in the arguments of the program, see [`USAGE`].
+///
+/// Return the reason why the arguments are not valid, if any.
+fn read_options(p_args: &mut Peekable<impl Iterator<Item = String>>) -> Result<Options, String> {
+    let mut l_options = Options {
+        mode: Mode::Play,
+        setup: state_machine::Setup::new(),
+        size: None,
+        cube_size: None,
+        win_length: None,
+        engines: [Some(ai::Settings::default()); 2],
+        misere: false,
+        gravity: false,
+        variant: None,
+        budget: None,
+    };
+    // The last option only available in the classic game, if any
+    let mut l_classic_option: Option<String> = None;
+
+    while let Some(l_arg) = p_args.next() {
+        // The file of `--analyse` is optional
+        if l_arg == "--analyse" {
+            l_options.mode = Mode::Analyse(p_args.next_if(|l_next| !l_next.starts_with("--")));
+            continue;
+        }
+        // The rules have no value
+        if l_arg == "--misere" || l_arg == "--gravity" {
+            if l_arg == "--misere" {
+                l_options.misere = true;
+            } else {
+                l_options.gravity = true;
+            }
+            l_classic_option = Some(l_arg);
+            continue;
+        }
+
+        let l_value = p_args
+            .next()
+            .ok_or_else(|| format!("Missing value of the argument {}", l_arg))?;
+        if ![
+            "--replay",
+            "--arena",
+            "--size",
+            "--color",
+            "--trace",
+            "--variant",
+        ]
+        .contains(&l_arg.as_str())
+        {
+            l_classic_option = Some(l_arg.clone());
+        }
+        match l_arg.as_str() {
+            "--load" => {
+                let l_game = game::load_game(&l_value)
+                    .map_err(|l_error| format!("Can not load the game, {}", l_error))?;
+                l_options.setup = l_options.setup.with_game(l_game);
+            }
+            "--replay" => l_options.mode = Mode::Replay(l_value),
+            "--arena" => match l_value.parse::<u32>() {
+                Ok(l_games) if l_games > 0 => l_options.mode = Mode::Arena(l_games),
+                _ => return Err(format!("`{}` is not a number of games", l_value)),
+            },
+            "--best-of" => match l_value.parse::<u32>() {
+                Ok(l_games) if l_games > 0 => {
+                    l_options.setup = l_options.setup.with_best_of(l_games)
+                }
+                _ => return Err(format!("`{}` is not a number of games", l_value)),
+            },
+            "--first" => {
+                let l_first_player = l_value
+                    .parse::<common::FirstPlayer>()
+                    .map_err(|l_error| format!("Bad first player, {}", l_error))?;
+                l_options.setup = l_options.setup.with_first_player(l_first_player);
+            }
+            "--size" => l_options.size = Some(l_value),
+            "--win" => {
+                let l_win_length = l_value
+                    .parse::<usize>()
+                    .map_err(|_| format!("`{}` is not a number of aligned symbols", l_value))?;
+                l_options.win_length = Some(l_win_length);
+            }
+            "--player-one" | "--player-two" => {
+                let (l_player, l_index) = if l_arg == "--player-one" {
+                    (common::Player::PlayerOne, 0)
+                } else {
+                    (common::Player::PlayerTwo, 1)
+                };
+                let l_engine =
+                    if l_value.eq_ignore_ascii_case("human") {
+                        None
+                    } else {
+                        Some(l_value.parse::<ai::Settings>().map_err(|l_error| {
+                            format!("Bad settings of the computer, {}", l_error)
+                        })?)
+                    };
+                let l_controller: Box<dyn player::PlayerController> = match l_engine {
+                    Some(l_settings) => Box::new(player::Computer::new(l_settings)),
+                    None => Box::new(player::Human),
+                };
+
+                l_options.engines[l_index] = l_engine;
+                l_options.setup = l_options.setup.with_player(l_player, l_controller);
+            }
+            "--script-one" | "--script-two" => {
+                let l_player = if l_arg == "--script-one" {
+                    common::Player::PlayerOne
+                } else {
+                    common::Player::PlayerTwo
+                };
+                let l_script = player::Scripted::from_file(&l_value)
+                    .map_err(|l_error| format!("Can not read the script, {}", l_error))?;
+                l_options.setup = l_options.setup.with_player(l_player, Box::new(l_script));
+            }
+            "--color" => match l_value.to_lowercase().as_str() {
+                "on" => screen::set_colors(true),
+                "off" => screen::set_colors(false),
+                _ => return Err(format!("`{}` is not on or off", l_value)),
+            },
+            "--trace" => tools::set_level(&l_value)?,
+            "--budget" => {
+                let l_budget = l_value
+                    .parse::<ai::alpha_beta::Budget>()
+                    .map_err(|l_error| format!("Bad budget, {}", l_error))?;
+                l_options.budget = Some(l_budget);
+            }
+            "--variant" => {
+                let l_variant = l_value
+                    .parse::<common::Variant>()
+                    .map_err(|l_error| format!("Bad game, {}", l_error))?;
+                l_options.variant = Some(l_variant);
+            }
+            _ => return Err(format!("Unknown argument {}, usage: {}", l_arg, USAGE)),
+        }
+    }
+
+    // The options of the classic game select it, the size is also the one of the cube of the qubic
+    match (l_options.variant, l_classic_option) {
+        (Some(common::Variant::Classic), _) | (_, None) => {}
+        (None, Some(_)) => l_options.variant = Some(common::Variant::Classic),
+        (Some(_), Some(l_option)) => {
+            return Err(format!(
+                "{} is only an option of the classic tic-tac-toe",
+                l_option
+            ))
+        }
+    }
+
+    // The records, the analysis and the arena are only available for the classic game
+    match (&l_options.mode, l_options.variant) {
+        (Mode::Play, _) | (_, None) | (_, Some(common::Variant::Classic)) => {}
+        (_, Some(_)) => {
+            return Err(String::from(
+                "--replay, --analyse and --arena are only options of the classic tic-tac-toe",
+            ))
+        }
+    }
+    if l_options.budget.is_some() && !matches!(l_options.mode, Mode::Analyse(_)) {
+        return Err(String::from("--budget is only an option of --analyse"));
+    }
+
+    if l_options.misere {
+        l_options.setup = l_options.setup.with_misere();
+    }
+    if l_options.gravity {
+        l_options.setup = l_options.setup.with_gravity();
+    }
+
+    // The size and the number of aligned symbols are checked now, as they can be given in any order
+    match (l_options.variant, &l_options.size) {
+        (Some(common::Variant::Qubic), Some(l_size)) => {
+            let l_cube_size = qubic::parse_size(l_size)
+                .map_err(|l_error| format!("Can not create the game, {}", l_error))?;
+            l_options.cube_size = Some(l_cube_size);
+        }
+        (Some(common::Variant::Ultimate), Some(_)) => {
+            return Err(String::from(
+                "--size is not an option of the ultimate tic-tac-toe",
+            ))
+        }
+        (_, Some(l_size)) => {
+            let l_game = game::new_game(l_size, l_options.win_length)
+                .map_err(|l_error| format!("Can not create the game, {}", l_error))?;
+            l_options.setup = l_options.setup.with_size(l_game.rows(), l_game.columns());
+            if l_options.win_length.is_some() {
+                l_options.setup = l_options.setup.with_win_length(l_game.win_length());
+            }
+            l_options.variant.get_or_insert(common::Variant::Classic);
+        }
+        (_, None) => {
+            if let Some(l_win_length) = l_options.win_length {
+                l_options.setup = l_options.setup.with_win_length(l_win_length);
+            }
+        }
+    }
+
+    Ok(l_options)
+}
+
+/// Ask which game is played, see [`common::Variant`].
+fn ask_variant() -> common::Variant {
+    screen::print("\x1B[34mWhich game ? t for the tic-tac-toe, u for the ultimate tic-tac-toe, c for the tic-tac-toe in a cube (empty for the tic-tac-toe):\x1B[0m ");
+
+    loop {
+        match game::read_keyboard().trim().to_lowercase().as_str() {
+            "" | "t" => return common::Variant::Classic,
+            "u" => return common::Variant::Ultimate,
+            "c" => return common::Variant::Qubic,
+            _ => screen::print("\x1B[41mBad entry, please enter t, u or c :\x1B[0m  "),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Read the options given as a command line
+    fn options(p_line: &str) -> Result<Options, String> {
+        read_options(&mut p_line.split_whitespace().map(String::from).peekable())
+    }
+
+    #[test]
+    fn variants_only_played() {
+        for l_line in [
+            "--variant ultimate --arena 2",
+            "--replay game.ttt --variant qubic",
+            "--analyse --variant ultimate",
+        ] {
+            assert!(options(l_line).is_err(), "{}", l_line);
+        }
+        assert!(options("--variant classic --arena 2").is_ok());
+        assert!(options("--variant qubic --size 4").is_ok());
+    }
+
+    #[test]
+    fn classic_options() {
+        assert!(options("--variant ultimate --size 4").is_err());
+        assert!(options("--variant ultimate --misere").is_err());
+        assert!(options("--variant qubic --gravity").is_err());
+        for l_line in ["--win 3", "--misere", "--gravity"] {
+            assert!(matches!(
+                options(l_line).map(|l_options| l_options.variant),
+                Ok(Some(common::Variant::Classic))
+            ));
+        }
+    }
+
+    #[test]
+    fn analysis_budget() {
+        assert!(options("--budget 2s").is_err());
+        assert!(options("--budget 2s --analyse").is_ok());
+        assert!(options("--analyse --budget x").is_err());
+    }
+
+    #[test]
+    fn numbers_of_games() {
+        assert!(options("--arena 0").is_err());
+        assert!(options("--arena 4").is_ok());
+        assert!(options("--best-of 0").is_err());
+        assert!(options("--best-of x").is_err());
+    }
+}
@@ -0,0 +1,435 @@
+//! Module in charge of who plays each player of a game.
+//!
+//! Each seat of a game is given a [`PlayerController`], asked by the [state machine](crate::state_machine) to play
+//! the turn of its player:
+//! - [`Human`]: a human entering the moves with the keyboard, see [`game::player_turn`]
+//! - [`Computer`]: the computer, see the [ai] module
+//! - [`NetworkPeer`]: a player of another program, connected with TCP
+//! - [`Scripted`]: a list of moves given in advance, to play a game without anybody at the keyboard
+//!
+//! After each turn, the controller of the other seat is notified of what has been played, so that a controller can
+//! follow the game, see [`PlayerController::notify`].
+//!
+//! # Network protocol
+//!
+//! The two programs send each other a line for each turn played on their side:
+//! - the number of the filled cell, as shown on the [screen], for a move, or the number of its column in a game played
+//!   with the gravity
+//! - `position <notation>` when the position has changed otherwise (undo, redo, loaded game), in the notation of the
+//!   [game] module
+//! - `q` when the player has quit the game
+//!
+//! One program waits for the connection on a port, the other connects to it, and each one gives the other player the
+//! seat it does not play. At the start of each game, both programs send `position <notation>`, the game is quit if
+//! they do not start the same game.
+//!
+//! The connection is made with the [`std::net`] streams directly: the `communication` prototype is a standalone chat
+//! program reading the keyboard, it can not carry the turns of a game.
+//!
+//! # Example
+//!
+//! ```rust
+//! mod player;
+//!
+//! let screen = screen::Screen::new_and_start();
+//! let mut game = game::Game::new(3, 3, 3);
+//! let mut players: [Box<dyn player::PlayerController>; 2] =
+//!     [Box::new(player::Human), Box::new(player::Computer::new(ai::Settings::default()))];
+//!
+//! let status = players[0].play_turn(&screen, &mut game);
+//! players[1].notify(&game, status);
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::{ai, game, screen, DEBUG, INFO, TRACE, WARNING};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Who plays a seat of a game
+pub trait PlayerController: Send {
+    /// Return the name of the player, as shown to the players and written in the records
+    fn name(&self) -> String;
+
+    /// Return `true` if a person plays the seat at this keyboard, who is asked to play again at the end of the game
+    fn is_human(&self) -> bool {
+        false
+    }
+
+    /// Return `true` if a person plays the seat, who plays again the moves undone, otherwise the move undone by the
+    /// other seat is undone with the move before it, see [`game::Game::undo`]
+    fn may_undo(&self) -> bool {
+        self.is_human()
+    }
+
+    /// Return `true` if the seat is played by another program, which can not share the coin tosses of this one
+    fn is_remote(&self) -> bool {
+        false
+    }
+
+    /// Called at the start of each game, return `false` if the seat can not play it and the game must be quit.
+    fn join_game(&mut self, _p_screen: &screen::Screen, _p_game: &game::Game) -> bool {
+        true
+    }
+
+    /// Plays the turn of the current player of the game.
+    ///
+    /// Return the [`game::TurnStatus`]: [`game::TurnStatus::Played`] once a move is made, [`game::TurnStatus::Changed`]
+    /// if the position has changed otherwise and [`game::TurnStatus::Quit`] if the player has quit the game.
+    fn play_turn(&mut self, p_screen: &screen::Screen, p_game: &mut game::Game)
+        -> game::TurnStatus;
+
+    /// Called after the turn of the other seat, with the game and what has been done during the turn.
+    fn notify(&mut self, _p_game: &game::Game, _p_status: game::TurnStatus) {}
+}
+
+/// A human entering the moves with the keyboard
+pub struct Human;
+
+impl PlayerController for Human {
+    fn name(&self) -> String {
+        String::from("Human")
+    }
+
+    fn is_human(&self) -> bool {
+        true
+    }
+
+    fn play_turn(
+        &mut self,
+        p_screen: &screen::Screen,
+        p_game: &mut game::Game,
+    ) -> game::TurnStatus {
+        game::player_turn(p_screen, p_game)
+    }
+}
+
+/// The computer, choosing its moves with the given settings
+pub struct Computer {
+    /// The settings of the computer
+    settings: ai::Settings,
+}
+
+impl Computer {
+    /// Create a computer player with the given settings.
+    pub fn new(p_settings: ai::Settings) -> Self {
+        Computer {
+            settings: p_settings,
+        }
+    }
+}
+
+impl PlayerController for Computer {
+    fn name(&self) -> String {
+        format!("Computer ({})", self.settings.level().name())
+    }
+
+    fn play_turn(
+        &mut self,
+        p_screen: &screen::Screen,
+        p_game: &mut game::Game,
+    ) -> game::TurnStatus {
+        ai::play_turn(p_screen, p_game, &self.settings)
+    }
+}
+
+/// A player of another program, connected with TCP, see the [network protocol](self#network-protocol)
+pub struct NetworkPeer {
+    /// The address of the other program
+    address: String,
+    /// The lines received from the other program
+    reader: BufReader<TcpStream>,
+    /// The connection to send the lines to the other program
+    writer: TcpStream,
+}
+
+impl NetworkPeer {
+    /// Connect to the program waiting at the given address, `<host>:<port>`.
+    pub fn connect(p_address: &str) -> Result<Self, String> {
+        let l_stream = TcpStream::connect(p_address)
+            .map_err(|l_error| format!("can not connect to {} ({})", p_address, l_error))?;
+
+        NetworkPeer::from_stream(l_stream)
+    }
+
+    /// Wait for a program to connect on the given port.
+    pub fn wait_on(p_port: u16) -> Result<Self, String> {
+        let l_listener = TcpListener::bind(("0.0.0.0", p_port))
+            .map_err(|l_error| format!("can not wait on the port {} ({})", p_port, l_error))?;
+        let (l_stream, _) = l_listener
+            .accept()
+            .map_err(|l_error| format!("can not accept the connection ({})", l_error))?;
+
+        NetworkPeer::from_stream(l_stream)
+    }
+}
+
+impl PlayerController for NetworkPeer {
+    fn name(&self) -> String {
+        format!("Network ({})", self.address)
+    }
+
+    fn may_undo(&self) -> bool {
+        true
+    }
+
+    fn is_remote(&self) -> bool {
+        true
+    }
+
+    /// Send the position of the game to the other program, and check that it starts the same game.
+    fn join_game(&mut self, p_screen: &screen::Screen, p_game: &game::Game) -> bool {
+        let l_position = format!("position {}", p_game.to_notation());
+        self.send_line(&l_position);
+
+        p_screen.send_msg("Waiting for the other player to start the game...");
+        let l_line = self.receive_line();
+        if l_line != l_position {
+            p_screen.send_msg(&format!(
+                "\x1B[41mThe other player does not start the same game, `{}` received\x1B[0m",
+                l_line
+            ));
+            return false;
+        }
+
+        true
+    }
+
+    fn play_turn(
+        &mut self,
+        p_screen: &screen::Screen,
+        p_game: &mut game::Game,
+    ) -> game::TurnStatus {
+        p_screen.send_msg("Waiting for the move of the other player...");
+
+        let l_line = self.receive_line();
+        let l_line = l_line.as_str();
+        if let Some(l_notation) = l_line.strip_prefix("position ") {
+            match game::Game::from_notation(l_notation) {
+                Ok(l_game) => {
+                    *p_game = l_game;
+                    return game::TurnStatus::Changed;
+                }
+                Err(l_error) => {
+                    p_screen.send_msg(&format!(
+                        "\x1B[41mThe other player sent a bad position, {}\x1B[0m",
+                        l_error
+                    ));
+                    return game::TurnStatus::Quit;
+                }
+            }
+        }
+
+        match l_line.parse::<usize>() {
+            Ok(l_number) => match play_number(p_game, l_number) {
+                Ok(()) if p_game.has_gravity() => {
+                    p_screen.send_msg(&format!(
+                        "The other player plays in the column {}",
+                        l_number
+                    ));
+                    game::TurnStatus::Played
+                }
+                Ok(()) => {
+                    p_screen.send_msg(&format!("The other player fills the cell {}", l_number));
+                    game::TurnStatus::Played
+                }
+                Err(l_error) => {
+                    p_screen.send_msg(&format!(
+                        "\x1B[41mThe move of the other player is refused, {}\x1B[0m",
+                        l_error
+                    ));
+                    game::TurnStatus::Quit
+                }
+            },
+            // `q`, or the connection is closed
+            Err(_) => {
+                p_screen.send_msg("\x1B[41mThe other player has left the game\x1B[0m");
+                game::TurnStatus::Quit
+            }
+        }
+    }
+
+    fn notify(&mut self, p_game: &game::Game, p_status: game::TurnStatus) {
+        let l_line = match (p_status, p_game.moves().last()) {
+            (game::TurnStatus::Played, Some(l_move)) if p_game.has_gravity() => {
+                l_move.column().to_string()
+            }
+            (game::TurnStatus::Played, Some(l_move)) => {
+                ((l_move.row() * p_game.columns()) + l_move.column()).to_string()
+            }
+            (game::TurnStatus::Quit, _) => String::from("q"),
+            _ => format!("position {}", p_game.to_notation()),
+        };
+
+        self.send_line(&l_line);
+    }
+}
+
+/// A list of moves given in advance, played one by one
+pub struct Scripted {
+    /// The numbers of the cells still to fill, as shown on the [screen], or of the columns in a game played with the
+    /// gravity
+    moves: VecDeque<usize>,
+}
+
+impl Scripted {
+    /// Create a player filling the given cells, in this order.
+    pub fn new(p_moves: Vec<usize>) -> Self {
+        Scripted {
+            moves: VecDeque::from(p_moves),
+        }
+    }
+
+    /// Read the numbers of the cells to fill, separated by spaces, commas or new lines.
+    pub fn from_text(p_text: &str) -> Result<Self, String> {
+        p_text
+            .split(|l_char: char| l_char.is_whitespace() || l_char == ',')
+            .filter(|l_entry| !l_entry.is_empty())
+            .map(|l_entry| {
+                l_entry
+                    .parse::<usize>()
+                    .map_err(|_| format!("`{}` is not a cell number", l_entry))
+            })
+            .collect::<Result<Vec<usize>, String>>()
+            .map(Scripted::new)
+    }
+
+    /// Read the numbers of the cells to fill in the file at the given path, see [`Scripted::from_text`].
+    pub fn from_file(p_path: &str) -> Result<Self, String> {
+        let l_content = fs::read_to_string(p_path)
+            .map_err(|l_error| format!("can not read {} ({})", p_path, l_error))?;
+
+        Scripted::from_text(&l_content)
+    }
+}
+
+impl PlayerController for Scripted {
+    fn name(&self) -> String {
+        String::from("Script")
+    }
+
+    /// Fill the next cell of the list, or drop in the next column with the gravity, the game is quit when the list is
+    /// over or when the move is refused.
+    fn play_turn(
+        &mut self,
+        p_screen: &screen::Screen,
+        p_game: &mut game::Game,
+    ) -> game::TurnStatus {
+        let l_number = match self.moves.pop_front() {
+            Some(l_number) => l_number,
+            None => {
+                p_screen.send_msg("\x1B[41mThe script has no more moves\x1B[0m");
+                return game::TurnStatus::Quit;
+            }
+        };
+
+        match play_number(p_game, l_number) {
+            Ok(()) if p_game.has_gravity() => {
+                p_screen.send_msg(&format!("The script plays in the column {}", l_number));
+                game::TurnStatus::Played
+            }
+            Ok(()) => {
+                p_screen.send_msg(&format!("The script fills the cell {}", l_number));
+                game::TurnStatus::Played
+            }
+            Err(l_error) => {
+                p_screen.send_msg(&format!(
+                    "\x1B[41mThe move {} of the script is refused, {}\x1B[0m",
+                    l_number, l_error
+                ));
+                game::TurnStatus::Quit
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl NetworkPeer {
+    /// Create the player of the other program connected with the given stream.
+    fn from_stream(p_stream: TcpStream) -> Result<Self, String> {
+        let l_address = p_stream
+            .peer_addr()
+            .map(|l_address| l_address.to_string())
+            .unwrap_or_else(|_| String::from("unknown"));
+        let l_writer = p_stream
+            .try_clone()
+            .map_err(|l_error| format!("can not use the connection ({})", l_error))?;
+
+        INFO!("[Player] Connected to {}", l_address);
+        Ok(NetworkPeer {
+            address: l_address,
+            reader: BufReader::new(p_stream),
+            writer: l_writer,
+        })
+    }
+
+    /// Send a line to the other program.
+    fn send_line(&mut self, p_line: &str) {
+        DEBUG!("[Player] Send `{}` to {}", p_line, self.address);
+        if let Err(l_error) = writeln!(self.writer, "{}", p_line) {
+            WARNING!("[Player] Can not send to {}, {}", self.address, l_error);
+        }
+    }
+
+    /// Return the next line received from the other program, without its end, empty if the connection is closed.
+    fn receive_line(&mut self) -> String {
+        let mut l_line = String::new();
+        if let Err(l_error) = self.reader.read_line(&mut l_line) {
+            WARNING!("[Player] Can not read from {}, {}", self.address, l_error);
+        }
+        DEBUG!(
+            "[Player] Received `{}` from {}",
+            l_line.trim(),
+            self.address
+        );
+
+        l_line.trim().to_string()
+    }
+}
+
+/// Fill the cell of the given number, or the lowest free cell of the column of the given number in a game played with
+/// the gravity, see [`game::change_cell`] and [`game::drop_in_column`].
+fn play_number(p_game: &mut game::Game, p_number: usize) -> Result<(), game::MoveError> {
+    if p_game.has_gravity() {
+        game::drop_in_column(p_game, p_number)
+    } else {
+        game::change_cell(p_game, p_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_drops_in_columns() {
+        let l_screen = screen::Screen::new_muted();
+        let mut l_game = game::Game::new(3, 3, 3).with_gravity(true);
+        let mut l_script = Scripted::new(vec![1, 1]);
+
+        assert_eq!(
+            l_script.play_turn(&l_screen, &mut l_game),
+            game::TurnStatus::Played
+        );
+        l_game.toggle_player();
+        assert_eq!(
+            l_script.play_turn(&l_screen, &mut l_game),
+            game::TurnStatus::Played
+        );
+        assert_eq!(l_game.to_notation(), "3x3 3 .../.O./.X. O gravity");
+    }
+}
@@ -0,0 +1,423 @@
+//! Module in charge of the tic-tac-toe in a cube, like the 4x4x4 _Qubic_.
+//!
+//! The grid is a cube of `n` layers of `n` rows and `n` columns, the first player filling a whole line of `n` cells
+//! wins: a row, a column or a diagonal of a layer, a vertical line through the layers, or a diagonal across the
+//! layers, up to the four space diagonals joining the opposite corners of the cube.
+//!
+//! The cells are numbered like the ones of the [game] module, layer after layer: the cell of the layer `l`, row `r`
+//! and column `c` is the number `(l * n * n) + (r * n) + c`, see [`change_cell`]. A move is entered as this number, or
+//! as `<layer> <row> <column>`, see [`player_turn`]. The game has its own loop, see [`run`], and is displayed layer by
+//! layer by the [`screen::Screen`].
+//!
+//! # Example
+//!
+//! ```rust
+//! mod qubic;
+//!
+//! let mut game = qubic::QubicGame::new(4);
+//! game.play(0, 0, 0).unwrap(); // A corner of the top layer
+//! qubic::change_cell(&mut game, 21).unwrap(); // The layer 1, row 1, column 1
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::{common, game, screen, DEBUG, INFO, TRACE};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The size of the cube of the _Qubic_, played when no other size is given
+pub const DEFAULT_SIZE: usize = 4;
+
+/// The structure saving all the elements of a game in a cube, see the [module](self).
+#[derive(Debug, Clone)]
+pub struct QubicGame {
+    /// The cells of the cube, indexed by layer, row and column
+    cells: Vec<Vec<Vec<game::Cell>>>,
+    /// The current player playing
+    current_player: common::Player,
+    /// The cells filled, in order: the layer, the row and the column
+    history: Vec<(usize, usize, usize)>,
+}
+
+impl QubicGame {
+    /// Create a new game in a cube of `p_size` layers, rows and columns, player one plays first.
+    pub fn new(p_size: usize) -> Self {
+        QubicGame {
+            cells: vec![vec![vec![game::Cell::Empty; p_size]; p_size]; p_size],
+            current_player: common::Player::PlayerOne,
+            history: Vec::new(),
+        }
+    }
+
+    /// Return the number of layers, rows and columns of the cube.
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Return the content of the cell at the given coordinate.
+    pub fn cell(&self, p_layer: usize, p_row: usize, p_column: usize) -> game::Cell {
+        self.cells[p_layer][p_row][p_column]
+    }
+
+    /// Return the current player, see [`common::Player`]
+    pub fn current_player(&self) -> common::Player {
+        self.current_player
+    }
+
+    /// Fill the cell with the current player, then give the turn to the other player.
+    ///
+    /// Return the [`game::MoveError`] explaining why the move is refused, if so.
+    pub fn play(
+        &mut self,
+        p_layer: usize,
+        p_row: usize,
+        p_column: usize,
+    ) -> Result<(), game::MoveError> {
+        if self.is_over() {
+            Err(game::MoveError::GameAlreadyOver)
+        } else if p_layer >= self.size() || p_row >= self.size() || p_column >= self.size() {
+            Err(game::MoveError::OutOfBounds)
+        } else if self.cells[p_layer][p_row][p_column] != game::Cell::Empty {
+            Err(game::MoveError::Occupied)
+        } else {
+            self.cells[p_layer][p_row][p_column] = game::Cell::Occupied(self.current_player);
+            self.history.push((p_layer, p_row, p_column));
+            self.current_player = self.current_player.opponent();
+            Ok(())
+        }
+    }
+
+    /// Cancel the last move, the player who made it becomes the current player.
+    /// Return `false` if there is no move to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((l_layer, l_row, l_column)) => {
+                self.cells[l_layer][l_row][l_column] = game::Cell::Empty;
+                self.current_player = self.current_player.opponent();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return the coordinates `(layer, row, column)` of the cells of the first full line of a player, if any.
+    pub fn winning_line(&self) -> Option<Vec<(usize, usize, usize)>> {
+        let l_size = self.size() as isize;
+
+        for i in 0..l_size {
+            for j in 0..l_size {
+                for k in 0..l_size {
+                    if self
+                        .cell_at(i, j, k)
+                        .is_none_or(|l_cell| l_cell == game::Cell::Empty)
+                    {
+                        continue;
+                    }
+                    for l_direction in directions() {
+                        let l_line: Vec<(isize, isize, isize)> = (0..l_size)
+                            .map(|l_step| {
+                                (
+                                    i + (l_step * l_direction.0),
+                                    j + (l_step * l_direction.1),
+                                    k + (l_step * l_direction.2),
+                                )
+                            })
+                            .collect();
+                        if l_line.iter().all(|(l_x, l_y, l_z)| {
+                            self.cell_at(*l_x, *l_y, *l_z) == self.cell_at(i, j, k)
+                        }) {
+                            return Some(
+                                l_line
+                                    .into_iter()
+                                    .map(|(l_x, l_y, l_z)| {
+                                        (l_x as usize, l_y as usize, l_z as usize)
+                                    })
+                                    .collect(),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Return the [`game::GameOutcome`] of the game in its current state.
+    pub fn outcome(&self) -> game::GameOutcome {
+        match self.winning_line() {
+            Some(l_line) => match self.cell(l_line[0].0, l_line[0].1, l_line[0].2) {
+                game::Cell::Occupied(l_winner) => game::GameOutcome::Won(l_winner),
+                // The lines are made of filled cells
+                game::Cell::Empty => game::GameOutcome::InProgress,
+            },
+            None if self.history.len() == self.size().pow(3) => game::GameOutcome::Draw,
+            None => game::GameOutcome::InProgress,
+        }
+    }
+
+    /// Test if the game is finished, by a win or a draw.
+    pub fn is_over(&self) -> bool {
+        self.outcome() != game::GameOutcome::InProgress
+    }
+
+    /// Return the content of the cell at the given coordinate, `None` if it is outside of the cube.
+    fn cell_at(&self, p_layer: isize, p_row: isize, p_column: isize) -> Option<game::Cell> {
+        let l_range = 0..self.size() as isize;
+        if l_range.contains(&p_layer) && l_range.contains(&p_row) && l_range.contains(&p_column) {
+            Some(self.cell(p_layer as usize, p_row as usize, p_column as usize))
+        } else {
+            None
+        }
+    }
+}
+
+/// Play a game in a cube of the given size between two players at the keyboard, displayed on its own
+/// [`screen::Screen`].
+///
+/// The size of the cube is asked if it is not given.
+pub fn run(p_size: Option<usize>) {
+    INFO!("[Qubic] Start the game");
+
+    let l_screen = screen::Screen::new_and_start();
+    let mut l_game = QubicGame::new(p_size.unwrap_or_else(|| ask_size(&l_screen)));
+
+    while !l_game.is_over() {
+        l_screen.send_qubic_game(&l_game);
+        if player_turn(&l_screen, &mut l_game) == game::TurnStatus::Quit {
+            l_screen.send_msg("The game has been quit");
+            l_screen.stop_and_free();
+            return;
+        }
+    }
+
+    l_screen.send_qubic_game(&l_game);
+    match l_game.outcome() {
+        game::GameOutcome::Won(common::Player::PlayerOne) => {
+            l_screen.send_msg("\x1B[32mPlayer one wins the game !\x1B[0m")
+        }
+        game::GameOutcome::Won(common::Player::PlayerTwo) => {
+            l_screen.send_msg("\x1B[31mPlayer two wins the game !\x1B[0m")
+        }
+        _ => l_screen.send_msg("It is a draw"),
+    }
+
+    INFO!("[Qubic] Game over : {:?}", l_game.outcome());
+    l_screen.stop_and_free();
+}
+
+/// Plays the current player's turn of the [`QubicGame`].
+///
+/// The player enters the number of a cell, `<layer> <row> <column>`, `u` to undo the last move or `q` to quit the game.
+/// As long as a valid entry has not been made, the player will be asked again.
+pub fn player_turn(p_screen: &screen::Screen, p_game: &mut QubicGame) -> game::TurnStatus {
+    game::announce_turn(
+        p_screen,
+        p_game.current_player(),
+        "the cell you want to fill, or <layer> <row> <column>",
+        "u to undo",
+    );
+
+    game::read_turn(p_screen, p_game, QubicGame::undo, |l_game, l_entry| {
+        let l_numbers: Vec<usize> = l_entry
+            .split_whitespace()
+            .map(|l_number| l_number.parse::<usize>().ok())
+            .collect::<Option<Vec<usize>>>()?;
+
+        match l_numbers.as_slice() {
+            [l_cell] => Some(change_cell(l_game, *l_cell)),
+            [l_layer, l_row, l_column] => Some(l_game.play(*l_layer, *l_row, *l_column)),
+            _ => None,
+        }
+    })
+}
+
+/// Fill the cell of the given number, as shown on the [`screen::Screen`], with the current player, see
+/// [`game::change_cell`].
+pub fn change_cell(p_game: &mut QubicGame, p_cell: usize) -> Result<(), game::MoveError> {
+    let l_size = p_game.size();
+    let l_layer: usize = p_cell / (l_size * l_size);
+    let l_row: usize = (p_cell / l_size) % l_size;
+    let l_column: usize = p_cell % l_size;
+    DEBUG!("[Qubic] Cell to change {} {} {}", l_layer, l_row, l_column);
+
+    p_game.play(l_layer, l_row, l_column)
+}
+
+/// Read the size of a cube, a number between 3 and 5.
+///
+/// Return the reason why the size is not valid, if so.
+pub fn parse_size(p_entry: &str) -> Result<usize, String> {
+    p_entry
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|l_size| (3..6).contains(l_size))
+        .ok_or_else(|| format!("`{}` is not a cube size between 3 and 5", p_entry.trim()))
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Return the 13 directions in which a line can be made, as `(layer, row, column)` steps: each one with its first step
+/// different from 0 being positive, so that a line is not found twice.
+fn directions() -> Vec<(isize, isize, isize)> {
+    let mut l_directions = Vec::new();
+    for i in -1..=1 {
+        for j in -1..=1 {
+            for k in -1..=1 {
+                if i > 0 || (i == 0 && j > 0) || (i == 0 && j == 0 && k > 0) {
+                    l_directions.push((i, j, k));
+                }
+            }
+        }
+    }
+
+    l_directions
+}
+
+/// Ask the size of the cube, [`DEFAULT_SIZE`] if the player enters nothing.
+fn ask_size(p_screen: &screen::Screen) -> usize {
+    p_screen.send_msg(&format!(
+        "\x1B[34mEnter the size of the cube, between 3 and 5 (empty for {}):\x1B[0m ",
+        DEFAULT_SIZE
+    ));
+
+    loop {
+        let l_entry = game::read_keyboard();
+        if l_entry.trim().is_empty() {
+            return DEFAULT_SIZE;
+        }
+        match parse_size(&l_entry) {
+            Ok(l_size) => return l_size,
+            Err(l_error) => p_screen.send_msg(&format!("\x1B[41mBad entry, {}\x1B[0m", l_error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return a 4x4x4 game where player one has filled the given cells, and the sorted cells of its winning line
+    fn winning_line(p_cells: &[(usize, usize, usize)]) -> Option<Vec<(usize, usize, usize)>> {
+        let mut l_game = QubicGame::new(4);
+        for (l_layer, l_row, l_column) in p_cells {
+            l_game.cells[*l_layer][*l_row][*l_column] =
+                game::Cell::Occupied(common::Player::PlayerOne);
+        }
+
+        l_game.winning_line().map(|mut l_line| {
+            l_line.sort_unstable();
+            l_line
+        })
+    }
+
+    /// Return the 4 cells from `p_start`, moving by `p_step` for each layer
+    fn line(
+        p_start: (usize, usize, usize),
+        p_step: (isize, isize, isize),
+    ) -> Vec<(usize, usize, usize)> {
+        let mut l_line: Vec<(usize, usize, usize)> = (0..4)
+            .map(|l_index| {
+                (
+                    (p_start.0 as isize + (l_index * p_step.0)) as usize,
+                    (p_start.1 as isize + (l_index * p_step.1)) as usize,
+                    (p_start.2 as isize + (l_index * p_step.2)) as usize,
+                )
+            })
+            .collect();
+        l_line.sort_unstable();
+        l_line
+    }
+
+    #[test]
+    fn lines_in_a_layer_and_through_the_layers() {
+        for l_line in [
+            line((2, 1, 0), (0, 0, 1)),
+            line((0, 0, 3), (0, 1, 0)),
+            line((1, 0, 3), (0, 1, -1)),
+            line((0, 2, 2), (1, 0, 0)),
+            line((0, 3, 0), (1, -1, 0)),
+        ] {
+            assert_eq!(winning_line(&l_line), Some(l_line));
+        }
+    }
+
+    #[test]
+    fn space_diagonals() {
+        for l_line in [
+            line((0, 0, 0), (1, 1, 1)),
+            line((0, 0, 3), (1, 1, -1)),
+            line((0, 3, 0), (1, -1, 1)),
+            line((0, 3, 3), (1, -1, -1)),
+        ] {
+            assert_eq!(winning_line(&l_line), Some(l_line));
+        }
+    }
+
+    #[test]
+    fn no_line() {
+        assert_eq!(winning_line(&line((0, 0, 0), (1, 1, 1))[..3]), None);
+        assert_eq!(
+            winning_line(&[(0, 0, 0), (1, 1, 1), (2, 2, 2), (3, 3, 2)]),
+            None
+        );
+    }
+
+    #[test]
+    fn every_line_of_a_small_cube() {
+        // The lines are the sets of 3 aligned cells, found from each cell in the 26 directions
+        let mut l_lines: Vec<Vec<(usize, usize, usize)>> = Vec::new();
+        for l_start in (0..27).map(|l_index| (l_index / 9, (l_index / 3) % 3, l_index % 3)) {
+            for l_step in
+                (0..27).map(|l_index| (l_index / 9 - 1, (l_index / 3) % 3 - 1, l_index % 3 - 1))
+            {
+                let l_end = (
+                    l_start.0 as isize + (2 * l_step.0),
+                    l_start.1 as isize + (2 * l_step.1),
+                    l_start.2 as isize + (2 * l_step.2),
+                );
+                let l_inside = [l_end.0, l_end.1, l_end.2]
+                    .iter()
+                    .all(|l_value| (0..3).contains(l_value));
+                if l_step != (0, 0, 0) && l_inside {
+                    let mut l_line: Vec<(usize, usize, usize)> = (0..3)
+                        .map(|l_index| {
+                            (
+                                (l_start.0 as isize + (l_index * l_step.0)) as usize,
+                                (l_start.1 as isize + (l_index * l_step.1)) as usize,
+                                (l_start.2 as isize + (l_index * l_step.2)) as usize,
+                            )
+                        })
+                        .collect();
+                    l_line.sort_unstable();
+                    if !l_lines.contains(&l_line) {
+                        l_lines.push(l_line);
+                    }
+                }
+            }
+        }
+        assert_eq!(l_lines.len(), 49);
+
+        for l_line in l_lines {
+            let mut l_game = QubicGame::new(3);
+            for (l_layer, l_row, l_column) in &l_line {
+                l_game.cells[*l_layer][*l_row][*l_column] =
+                    game::Cell::Occupied(common::Player::PlayerTwo);
+            }
+
+            let mut l_found = l_game.winning_line().unwrap();
+            l_found.sort_unstable();
+            assert_eq!(l_found, l_line);
+        }
+    }
+}
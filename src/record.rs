@@ -0,0 +1,374 @@
+//! Module in charge of the records of the games, to keep a trace of every game played and to replay them.
+//!
+//! A [`GameRecord`] is written in a text format inspired by the _Portable Game Notation_ of the chess. It begins with
+//! tags giving the metadata of the game, one per line, followed by the numbered list of the moves and the result:
+//!
+//! ```text
+//! [Event "Tic-Tac-Toe"]
+//! [Date "2026.10.18"]
+//! [PlayerOne "Human"]
+//! [PlayerTwo "Human"]
+//! [Size "3x3"]
+//! [WinLength "3"]
+//! [Result "1-0"]
+//!
+//! 1. 4 0 2. 2 6 3. 3 5 4. 8 1-0
+//! ```
+//!
+//! A move is the number of the filled cell, as shown on the [screen]. The result is `1-0` if player one won, `0-1` if
+//! player two won, `1/2-1/2` for a draw and `*` for an unfinished game. When the game did not start from an empty
+//! grid or with other rules, the tag `Position` gives the starting position in the notation of the [game] module.
+//!
+//! # Example
+//!
+//! ```rust
+//! mod record;
+//!
+//! let record = record::GameRecord::from_game(&game, "Human", "Human");
+//! record.save(record::RECORDS_DIRECTORY);
+//!
+//! let screen = screen::Screen::new_and_start();
+//! record::replay(&screen, &record);
+//! screen.stop_and_free();
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::{common, game, screen, DEBUG, TRACE};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The directory where the games played are recorded
+pub const RECORDS_DIRECTORY: &str = "records";
+
+/// The record of a game: its metadata, starting position and moves.
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    /// The date of the game, `YYYY.MM.DD`
+    date: String,
+    /// The name of player one
+    player_one: String,
+    /// The name of player two
+    player_two: String,
+    /// The position before the first move
+    start: game::Game,
+    /// The numbers of the filled cells, in order
+    moves: Vec<usize>,
+    /// The result of the game, `1-0`, `0-1`, `1/2-1/2` or `*`
+    result: String,
+}
+
+impl GameRecord {
+    /// Create the record of the given game, played today.
+    pub fn from_game(p_game: &game::Game, p_player_one: &str, p_player_two: &str) -> Self {
+        let mut l_start = p_game.clone();
+        while l_start.undo() {}
+
+        GameRecord {
+            date: today(),
+            player_one: String::from(p_player_one),
+            player_two: String::from(p_player_two),
+            moves: p_game
+                .moves()
+                .iter()
+                .map(|l_move| l_move.row() * p_game.columns() + l_move.column())
+                .collect(),
+            result: String::from(match p_game.outcome() {
+                game::GameOutcome::Won(common::Player::PlayerOne) => "1-0",
+                game::GameOutcome::Won(common::Player::PlayerTwo) => "0-1",
+                game::GameOutcome::Draw => "1/2-1/2",
+                game::GameOutcome::InProgress => "*",
+            }),
+            start: l_start,
+        }
+    }
+
+    /// Read a record written in the format described in the [module documentation](self).
+    ///
+    /// Return the reason why the text is not a valid record, if so.
+    pub fn from_text(p_text: &str) -> Result<Self, String> {
+        let mut l_tags: Vec<(String, String)> = Vec::new();
+        let mut l_moves: Vec<usize> = Vec::new();
+
+        for l_line in p_text.lines().map(str::trim) {
+            if l_line.starts_with('[') && l_line.ends_with(']') {
+                let l_tag = &l_line[1..l_line.len() - 1];
+                let (l_name, l_value) = l_tag
+                    .split_once(' ')
+                    .ok_or_else(|| format!("the tag `{}` has no value", l_line))?;
+                l_tags.push((
+                    String::from(l_name),
+                    String::from(l_value.trim().trim_matches('"')),
+                ));
+            } else {
+                for l_token in l_line.split_whitespace() {
+                    if l_token.ends_with('.') || RESULTS.contains(&l_token) {
+                        continue;
+                    }
+                    l_moves.push(
+                        l_token
+                            .parse::<usize>()
+                            .map_err(|_| format!("`{}` is not a move", l_token))?,
+                    );
+                }
+            }
+        }
+
+        let l_tag = |p_name: &str| {
+            l_tags
+                .iter()
+                .find(|(l_name, _)| l_name == p_name)
+                .map(|(_, l_value)| l_value.clone())
+        };
+
+        let l_start = match l_tag("Position") {
+            Some(l_position) => {
+                game::Game::from_notation(&l_position).map_err(|l_error| l_error.to_string())?
+            }
+            None => {
+                let l_size = l_tag("Size").ok_or("the tag `Size` is missing")?;
+                let l_win_length = l_tag("WinLength").ok_or("the tag `WinLength` is missing")?;
+                game::Game::from_notation(&format!(
+                    "{} {} {} X",
+                    l_size,
+                    l_win_length,
+                    empty_cells(&l_size)?
+                ))
+                .map_err(|l_error| l_error.to_string())?
+            }
+        };
+
+        let l_cells = l_start.rows() * l_start.columns();
+        if let Some(l_cell) = l_moves.iter().find(|l_cell| **l_cell >= l_cells) {
+            return Err(format!("the move {} is out of the grid", l_cell));
+        }
+
+        Ok(GameRecord {
+            date: l_tag("Date").unwrap_or_else(|| String::from("????.??.??")),
+            player_one: l_tag("PlayerOne").unwrap_or_else(|| String::from("?")),
+            player_two: l_tag("PlayerTwo").unwrap_or_else(|| String::from("?")),
+            start: l_start,
+            moves: l_moves,
+            result: l_tag("Result").unwrap_or_else(|| String::from("*")),
+        })
+    }
+
+    /// Write the record in the format described in the [module documentation](self).
+    pub fn to_text(&self) -> String {
+        let mut l_text = String::from("[Event \"Tic-Tac-Toe\"]\n");
+        l_text.push_str(&format!("[Date \"{}\"]\n", self.date));
+        l_text.push_str(&format!("[PlayerOne \"{}\"]\n", self.player_one));
+        l_text.push_str(&format!("[PlayerTwo \"{}\"]\n", self.player_two));
+        l_text.push_str(&format!(
+            "[Size \"{}x{}\"]\n",
+            self.start.rows(),
+            self.start.columns()
+        ));
+        l_text.push_str(&format!("[WinLength \"{}\"]\n", self.start.win_length()));
+        if !self.starts_from_empty_grid() {
+            l_text.push_str(&format!("[Position \"{}\"]\n", self.start.to_notation()));
+        }
+        l_text.push_str(&format!("[Result \"{}\"]\n\n", self.result));
+
+        for (i, l_cell) in self.moves.iter().enumerate() {
+            if i % 2 == 0 {
+                l_text.push_str(&format!("{}. ", (i / 2) + 1));
+            }
+            l_text.push_str(&format!("{} ", l_cell));
+        }
+        l_text.push_str(&format!("{}\n", self.result));
+
+        l_text
+    }
+
+    /// Write the record in a new file of the given directory, created if needed.
+    ///
+    /// Return the path of the file written, or the reason why it can not be written.
+    pub fn save(&self, p_directory: &str) -> Result<String, String> {
+        fs::create_dir_all(p_directory)
+            .map_err(|l_error| format!("can not create {} ({})", p_directory, l_error))?;
+
+        let l_seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|l_duration| l_duration.as_secs())
+            .unwrap_or(0);
+        let mut l_path = Path::new(p_directory).join(format!("game-{}.ttt", l_seconds));
+        let mut l_index = 1;
+        while l_path.exists() {
+            l_path = Path::new(p_directory).join(format!("game-{}-{}.ttt", l_seconds, l_index));
+            l_index += 1;
+        }
+
+        fs::write(&l_path, self.to_text())
+            .map_err(|l_error| format!("can not write {} ({})", l_path.display(), l_error))?;
+
+        Ok(l_path.display().to_string())
+    }
+}
+
+/// Read the record saved in the file at the given path, see [`GameRecord::from_text`].
+pub fn load_record(p_path: &str) -> Result<GameRecord, String> {
+    let l_content = fs::read_to_string(p_path)
+        .map_err(|l_error| format!("can not read {} ({})", p_path, l_error))?;
+
+    GameRecord::from_text(&l_content)
+}
+
+/// Replay a recorded game on the [`screen::Screen`], one move each time the user presses `Enter`.
+///
+/// The replay stops at the end of the game, or when the user enters `q`.
+pub fn replay(p_screen: &screen::Screen, p_record: &GameRecord) {
+    let mut l_game = p_record.start.clone();
+
+    p_screen.send_game(&l_game);
+    p_screen.send_msg(&format!(
+        "{} (X) against {} (O), the {}. Press Enter for the next move, q to quit",
+        p_record.player_one, p_record.player_two, p_record.date
+    ));
+
+    for (i, l_cell) in p_record.moves.iter().enumerate() {
+        if game::read_keyboard().trim().eq_ignore_ascii_case("q") {
+            return;
+        }
+
+        if let Err(l_error) = game::change_cell(&mut l_game, *l_cell) {
+            p_screen.send_msg(&format!(
+                "\x1B[41mThe move {} ({}) can not be played, {}\x1B[0m",
+                i + 1,
+                l_cell,
+                l_error
+            ));
+            return;
+        }
+        DEBUG!("[Record] Move {} : {}", i + 1, l_cell);
+        l_game.toggle_player();
+
+        p_screen.send_game(&l_game);
+        p_screen.send_msg(&format!("Move {}/{} : {}", i + 1, p_record.moves.len(), l_cell));
+    }
+
+    p_screen.send_msg(&format!("End of the game, result {}", p_record.result));
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The possible results of a game
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+impl GameRecord {
+    /// Test if the game started from an empty grid with player one to play, with the usual rules.
+    fn starts_from_empty_grid(&self) -> bool {
+        self.start.current_player() == common::Player::PlayerOne
+            && !self.start.is_misere()
+            && !self.start.has_gravity()
+            && (0..self.start.rows()).all(|i| {
+                (0..self.start.columns()).all(|j| self.start.cell(i, j) == game::Cell::Empty)
+            })
+    }
+}
+
+/// Return the cells of an empty grid of the given size `<rows>x<columns>`, in the notation of the [game] module.
+fn empty_cells(p_size: &str) -> Result<String, String> {
+    let (l_rows, l_columns) = p_size
+        .split_once('x')
+        .and_then(|(l_rows, l_columns)| {
+            Some((l_rows.parse::<usize>().ok()?, l_columns.parse::<usize>().ok()?))
+        })
+        .ok_or_else(|| format!("`{}` is not a grid size", p_size))?;
+
+    Ok(vec![".".repeat(l_columns); l_rows].join("/"))
+}
+
+/// Return the date of today, `YYYY.MM.DD`
+fn today() -> String {
+    let l_days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|l_duration| l_duration.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+
+    // Conversion of a number of days since 1970-01-01 to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let l_shifted = l_days + 719_468;
+    let l_era = l_shifted.div_euclid(146_097);
+    let l_day_of_era = l_shifted - l_era * 146_097;
+    let l_year_of_era =
+        (l_day_of_era - l_day_of_era / 1460 + l_day_of_era / 36_524 - l_day_of_era / 146_096) / 365;
+    let l_day_of_year = l_day_of_era - (365 * l_year_of_era + l_year_of_era / 4 - l_year_of_era / 100);
+    let l_month_index = (5 * l_day_of_year + 2) / 153;
+    let l_day = l_day_of_year - (153 * l_month_index + 2) / 5 + 1;
+    let l_month = if l_month_index < 10 {
+        l_month_index + 3
+    } else {
+        l_month_index - 9
+    };
+    let l_year = l_year_of_era + l_era * 400 + if l_month <= 2 { 1 } else { 0 };
+
+    format!("{:04}.{:02}.{:02}", l_year, l_month, l_day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let mut l_game = game::Game::from_notation("3x3 3 .../.O./... X").unwrap();
+        for l_cell in &[0, 8, 1, 7, 2] {
+            game::change_cell(&mut l_game, *l_cell).unwrap();
+        }
+
+        let l_text = GameRecord::from_game(&l_game, "Alice", "Bob").to_text();
+        assert!(l_text.contains("[Position \"3x3 3 .../.O./... X\"]\n"));
+        assert!(l_text.ends_with("1. 0 8 2. 1 7 3. 2 1-0\n"));
+
+        let l_record = GameRecord::from_text(&l_text).unwrap();
+        assert_eq!(l_record.player_one, "Alice");
+        assert_eq!(l_record.player_two, "Bob");
+        assert_eq!(l_record.start.to_notation(), "3x3 3 .../.O./... X");
+        assert_eq!(l_record.moves, vec![0, 8, 1, 7, 2]);
+        assert_eq!(l_record.result, "1-0");
+        assert_eq!(l_record.to_text(), l_text);
+    }
+
+    #[test]
+    fn empty_grid_without_position() {
+        let mut l_game = game::Game::new(3, 4, 3);
+        game::change_cell(&mut l_game, 6).unwrap();
+
+        let l_text = GameRecord::from_game(&l_game, "Human", "Computer").to_text();
+        assert!(l_text.contains("[Size \"3x4\"]\n[WinLength \"3\"]\n[Result \"*\"]\n"));
+        assert!(!l_text.contains("Position"));
+
+        let l_record = GameRecord::from_text(&l_text).unwrap();
+        assert_eq!(l_record.start.to_notation(), "3x4 3 ..../..../.... X");
+        assert_eq!(l_record.moves, vec![6]);
+        assert_eq!(l_record.result, "*");
+    }
+
+    #[test]
+    fn invalid_records() {
+        assert_eq!(
+            GameRecord::from_text("[Size \"3x3\"]\n\n1. 4 *").err(),
+            Some(String::from("the tag `WinLength` is missing"))
+        );
+        assert_eq!(
+            GameRecord::from_text("[Size \"3x3\"]\n[WinLength \"3\"]\n\n1. 4 a4 *").err(),
+            Some(String::from("`a4` is not a move"))
+        );
+        assert_eq!(
+            GameRecord::from_text("[Size \"3x3\"]\n[WinLength \"3\"]\n\n1. 4 9 *").err(),
+            Some(String::from("the move 9 is out of the grid"))
+        );
+    }
+}
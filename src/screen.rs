@@ -4,6 +4,8 @@
 //! independent of the game management. It is thus possible to have several parts to display at the same time.
 //! It is then easy to switch from the display in the terminal to a more complex game window.
 //!
+//! The colors can be disabled for the terminals that do not support them, see [`set_colors`].
+//!
 //! # Example
 //!
 //! ```rust
@@ -30,8 +32,9 @@
 //! - Pierre-Louis GAUTIER
 //! - Damien FRISSANT
 
-use crate::game;
+use crate::{common, game, qubic, ultimate};
 use crate::{INFO, TRACE};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::thread;
@@ -64,6 +67,20 @@ impl Screen {
         }
     }
 
+    /// Create and launch a screen displaying nothing, for the games played without anybody watching them, see
+    /// [`run_muted`]
+    pub fn new_muted() -> Self {
+        INFO!("[Screen] Event : Create a muted Screen");
+
+        let (l_sender, l_receiver): (Sender<MqScreen>, Receiver<MqScreen>) = mpsc::channel();
+        Self {
+            sender: l_sender,
+            handler: thread::spawn(move || {
+                run_muted(&l_receiver);
+            }),
+        }
+    }
+
     /// Send the to quit the screen ([`Screen::send_quit`]), and block the current thread until the [`Screen`]'s thread is not terminated
     pub fn stop_and_free(self) {
         INFO!("[Screen] Event : Stop the state machine");
@@ -94,12 +111,43 @@ impl Screen {
     pub fn send_game(&self, p_game: &game::Game) {
         self.sender
             .send(MqScreen::CurrentGrid {
-                game: p_game.clone()
+                game: p_game.clone(),
+            })
+            .expect("[Screen] Error can't send a message");
+    }
+
+    /// Send an [ultimate::UltimateGame] to display to the [`Screen`]
+    pub fn send_ultimate_game(&self, p_game: &ultimate::UltimateGame) {
+        self.sender
+            .send(MqScreen::CurrentUltimateGrid {
+                game: p_game.clone(),
+            })
+            .expect("[Screen] Error can't send a message");
+    }
+
+    /// Send a [qubic::QubicGame] to display to the [`Screen`]
+    pub fn send_qubic_game(&self, p_game: &qubic::QubicGame) {
+        self.sender
+            .send(MqScreen::CurrentQubicGrid {
+                game: p_game.clone(),
             })
             .expect("[Screen] Error can't send a message");
     }
 }
 
+/// Enable or disable the colors of the texts displayed, see [`print`].
+pub fn set_colors(p_enabled: bool) {
+    COLORS.store(p_enabled, Ordering::Relaxed);
+}
+
+/// Display the text in the terminal, without its colors if they are disabled, see [`set_colors`].
+pub fn print(p_text: &str) {
+    if COLORS.load(Ordering::Relaxed) {
+        println!("{}", p_text);
+    } else {
+        println!("{}", strip_colors(p_text));
+    }
+}
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 //
@@ -113,6 +161,8 @@ impl Screen {
 enum MqScreen {
     Message { msg: String },
     CurrentGrid { game: game::Game },
+    CurrentUltimateGrid { game: ultimate::UltimateGame },
+    CurrentQubicGrid { game: qubic::QubicGame },
     Quit,
 }
 
@@ -125,11 +175,19 @@ fn run(p_receiver: &Receiver<MqScreen>) {
             MqScreen::CurrentGrid { game } => {
                 l_current_game = game;
                 println!("\x1B[2J\x1B[1;1H");
-                println!("{}", l_current_game);
+                print(&render_grid(&l_current_game));
+            }
+            MqScreen::CurrentUltimateGrid { game } => {
+                println!("\x1B[2J\x1B[1;1H");
+                print(&render_ultimate_grid(&game));
+            }
+            MqScreen::CurrentQubicGrid { game } => {
+                println!("\x1B[2J\x1B[1;1H");
+                print(&render_qubic_grid(&game));
             }
 
             MqScreen::Message { msg } => {
-                println!("{}", msg);
+                print(&msg);
             }
             MqScreen::Quit => {
                 println!("Good by");
@@ -138,3 +196,181 @@ fn run(p_receiver: &Receiver<MqScreen>) {
         }
     }
 }
+
+/// Set if the texts are displayed with their colors, see [`set_colors`]
+static COLORS: AtomicBool = AtomicBool::new(true);
+
+/// Return the text without its colors: the escape sequences `ESC [ ... m` are removed.
+fn strip_colors(p_text: &str) -> String {
+    let mut l_text = String::with_capacity(p_text.len());
+    let mut l_chars = p_text.chars();
+
+    while let Some(l_char) = l_chars.next() {
+        if l_char == '\x1B' && l_chars.clone().next() == Some('[') {
+            let l_sequence: String = l_chars
+                .clone()
+                .take_while(|l_next| *l_next != 'm')
+                .collect();
+            if l_sequence[1..]
+                .chars()
+                .all(|l_next| l_next.is_ascii_digit() || l_next == ';')
+            {
+                // Skip the sequence and its final `m`
+                l_chars.nth(l_sequence.chars().count());
+                continue;
+            }
+        }
+        l_text.push(l_char);
+    }
+
+    l_text
+}
+
+/// Receive the messages like [`run`], but display none of them.
+fn run_muted(p_receiver: &Receiver<MqScreen>) {
+    while let Ok(l_message) = p_receiver.recv() {
+        if let MqScreen::Quit = l_message {
+            break;
+        }
+    }
+}
+
+/// Return the symbol to draw for the given player, see [`common::PLAYER_ONE_SYMBOL`] and [`common::PLAYER_TWO_SYMBOL`]
+///
+/// If the cell is part of a winning line, the highlighted symbols are used, see [`common::PLAYER_ONE_WINNING_SYMBOL`]
+/// and [`common::PLAYER_TWO_WINNING_SYMBOL`]
+fn player_symbol(p_player: common::Player, p_highlighted: bool) -> &'static str {
+    match (p_player, p_highlighted) {
+        (common::Player::PlayerOne, false) => common::PLAYER_ONE_SYMBOL,
+        (common::Player::PlayerTwo, false) => common::PLAYER_TWO_SYMBOL,
+        (common::Player::PlayerOne, true) => common::PLAYER_ONE_WINNING_SYMBOL,
+        (common::Player::PlayerTwo, true) => common::PLAYER_TWO_WINNING_SYMBOL,
+    }
+}
+
+/// Build the text representation of the grid of a [`game::Game`].
+///
+/// An empty cell shows its number, the one to enter to fill it, an occupied cell shows the symbol of its player. With the
+/// gravity, only the lowest free cell of each column shows a number, the one of its column.
+/// The cells of the winning lines, if any, are highlighted. With the misère rules, they are the losing lines, and the
+/// rules are reminded above the grid.
+fn render_grid(p_game: &game::Game) -> String {
+    let l_victory = p_game.victory();
+    let l_separator = format!("{}+\n", "+-----".repeat(p_game.columns()));
+    let mut l_grid: String = if p_game.is_misere() {
+        String::from("Misère rules: the player completing a line loses\n")
+    } else {
+        String::new()
+    };
+    l_grid.push_str(&l_separator);
+    for i in 0..p_game.rows() {
+        l_grid.push('|');
+        for j in 0..p_game.columns() {
+            match p_game.cell(i, j) {
+                game::Cell::Empty if p_game.has_gravity() => {
+                    if p_game.lowest_free_cell(j) == Some((i, j)) {
+                        l_grid.push_str(&format!("{:^5}|", j))
+                    } else {
+                        l_grid.push_str("     |")
+                    }
+                }
+                game::Cell::Empty => {
+                    l_grid.push_str(&format!("{:^5}|", (p_game.columns() * i) + j))
+                }
+                game::Cell::Occupied(l_player) => {
+                    let l_highlighted = l_victory
+                        .as_ref()
+                        .is_some_and(|l_victory| l_victory.contains(i, j));
+                    l_grid.push_str(&format!("{:^5}|", player_symbol(l_player, l_highlighted)))
+                }
+            }
+        }
+        l_grid.push('\n');
+        l_grid.push_str(&l_separator);
+    }
+
+    l_grid
+}
+
+/// Build the text representation of the boards of an [`ultimate::UltimateGame`], placed like the cells of the
+/// meta-board.
+///
+/// An empty cell of a board where the current player can play shows `<board> <cell>`, the move to enter to fill it,
+/// the other empty cells are blank. The cells of the winning line of each board are highlighted.
+fn render_ultimate_grid(p_game: &ultimate::UltimateGame) -> String {
+    let l_separator = format!(
+        "{}\n",
+        ["+-----".repeat(3), String::from("+")].concat().repeat(3)
+    );
+    let mut l_grid = String::new();
+    for l_meta_row in 0..3 {
+        l_grid.push_str(&l_separator);
+        for i in 0..3 {
+            for l_meta_column in 0..3 {
+                let l_board_number = (l_meta_row * 3) + l_meta_column;
+                let l_board = p_game.board(l_board_number);
+                let l_victory = l_board.victory();
+                l_grid.push('|');
+                for j in 0..3 {
+                    match l_board.cell(i, j) {
+                        game::Cell::Empty if p_game.is_playable(l_board_number) => l_grid.push_str(
+                            &format!("{:^5}|", format!("{} {}", l_board_number, (i * 3) + j)),
+                        ),
+                        game::Cell::Empty => l_grid.push_str("     |"),
+                        game::Cell::Occupied(l_player) => {
+                            let l_highlighted = l_victory
+                                .as_ref()
+                                .is_some_and(|l_victory| l_victory.contains(i, j));
+                            l_grid.push_str(&format!(
+                                "{:^5}|",
+                                player_symbol(l_player, l_highlighted)
+                            ))
+                        }
+                    }
+                }
+            }
+            l_grid.push('\n');
+            l_grid.push_str(&l_separator);
+        }
+        l_grid.push('\n');
+    }
+
+    l_grid
+}
+
+/// Build the text representation of the cube of a [`qubic::QubicGame`], layer by layer from the top.
+///
+/// An empty cell shows its number, the one to enter to fill it, see [`qubic::change_cell`]. The cells of the winning
+/// line, if any, are highlighted.
+fn render_qubic_grid(p_game: &qubic::QubicGame) -> String {
+    let l_size = p_game.size();
+    let l_line = p_game.winning_line();
+    let l_separator = format!("{}+\n", "+-----".repeat(l_size));
+    let mut l_grid = String::new();
+    for l_layer in 0..l_size {
+        l_grid.push_str(&format!("Layer {}\n", l_layer));
+        l_grid.push_str(&l_separator);
+        for i in 0..l_size {
+            l_grid.push('|');
+            for j in 0..l_size {
+                match p_game.cell(l_layer, i, j) {
+                    game::Cell::Empty => l_grid.push_str(&format!(
+                        "{:^5}|",
+                        (l_layer * l_size * l_size) + (i * l_size) + j
+                    )),
+                    game::Cell::Occupied(l_player) => {
+                        let l_highlighted = l_line
+                            .as_ref()
+                            .is_some_and(|l_line| l_line.contains(&(l_layer, i, j)));
+                        l_grid.push_str(&format!("{:^5}|", player_symbol(l_player, l_highlighted)))
+                    }
+                }
+            }
+            l_grid.push('\n');
+            l_grid.push_str(&l_separator);
+        }
+        l_grid.push('\n');
+    }
+
+    l_grid
+}
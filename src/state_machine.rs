@@ -3,16 +3,35 @@
 //! An instance [`StateMachine`] is created and works in its own thread, thus allowing to have possibly several parts at the same time if
 //! one wishes to make the program evolve in that direction.
 //!
+//! At the end of every game, the game is recorded in the directory [`record::RECORDS_DIRECTORY`], and the players are
+//! offered a rematch, the first player of each game being chosen as set, see [`common::FirstPlayer`]. A series of games can also be played,
+//! the first player winning more than half of the games winning the series, see [`Setup::with_best_of`].
+//!
 //! # Examples
 //!
 //! ```rust
 //! mod state_machine;
 //!
-//! let game_state_machine = state_machine::StateMachine::new_and_start();
+//! let game_state_machine = state_machine::StateMachine::new_and_start(state_machine::Setup::new());
 //! game_state_machine.start_game();
 //! game_state_machine.wait_end_game();
 //! ```
 //!
+//! The elements of the game known in advance are given in the [`Setup`], only the missing ones are asked to the
+//! players. A game between two computers can be played headless, without displaying nor recording it:
+//!
+//! ```rust
+//! let setup = state_machine::Setup::new()
+//!     .with_game(game::Game::new(3, 3, 3))
+//!     .with_player(common::Player::PlayerOne, Box::new(player::Computer::new(ai::Settings::default())))
+//!     .with_player(common::Player::PlayerTwo, Box::new(player::Computer::new(ai::Settings::default())))
+//!     .headless();
+//!
+//! let game_state_machine = state_machine::StateMachine::new_and_start(setup);
+//! game_state_machine.start_game();
+//! let outcome = game_state_machine.wait_end_game();
+//! ```
+//!
 //! # Resources
 //! The state machine has been realized with the help of :
 //! - [Ana Hoverbear](https://hoverbear.org/blog/rust-state-machine-pattern/)
@@ -21,7 +40,8 @@
 //! # Author
 //! Pierre-Louis GAUTIER
 
-use crate::{common, game, screen, DEBUG, INFO, TRACE, WARNING};
+use crate::{common, game, player, record, screen, DEBUG, INFO, TRACE, WARNING};
+use std::cmp::Ordering;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::thread;
@@ -32,17 +52,111 @@ use std::thread;
 //
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Who plays player one and player two, see [`player::PlayerController`]
+pub type Players = [Box<dyn player::PlayerController>; 2];
+
+/// The elements of a game known before it starts, the missing ones are asked to the players
+#[derive(Default)]
+pub struct Setup {
+    /// The game to play, otherwise the players are asked to create one, see [`game::create_game`]
+    game: Option<game::Game>,
+    /// The number of rows and columns of the grid of the game to create, otherwise it is asked
+    size: Option<(usize, usize)>,
+    /// The number of aligned symbols needed to win the game to create, otherwise it is asked
+    win_length: Option<usize>,
+    /// Who plays each player, otherwise it is asked, see [`game::ask_player`]
+    players: [Option<Box<dyn player::PlayerController>>; 2],
+    /// If set, the game is neither displayed nor recorded
+    headless: bool,
+    /// The number of games of the series, if any
+    best_of: Option<u32>,
+    /// Who starts the games, otherwise it is asked when the game is created, see [`game::ask_first_player`]
+    first_player: Option<common::FirstPlayer>,
+    /// If set, the game created is played with the misère rules, see [`game::Game::with_misere`]
+    misere: bool,
+    /// If set, the game created is played with the gravity, see [`game::Game::with_gravity`]
+    gravity: bool,
+}
+
+impl Setup {
+    /// Create a setup where everything is asked to the players.
+    pub fn new() -> Self {
+        Setup::default()
+    }
+
+    /// Play the given game.
+    pub fn with_game(mut self, p_game: game::Game) -> Self {
+        self.game = Some(p_game);
+        self
+    }
+
+    /// Create the game on a grid of the given size, when no game is given.
+    pub fn with_size(mut self, p_rows: usize, p_columns: usize) -> Self {
+        self.size = Some((p_rows, p_columns));
+        self
+    }
+
+    /// Set the number of aligned symbols needed to win the game to create, when no game is given.
+    pub fn with_win_length(mut self, p_win_length: usize) -> Self {
+        self.win_length = Some(p_win_length);
+        self
+    }
+
+    /// Set who plays the given player.
+    pub fn with_player(
+        mut self,
+        p_player: common::Player,
+        p_controller: Box<dyn player::PlayerController>,
+    ) -> Self {
+        self.players[player_index(p_player)] = Some(p_controller);
+        self
+    }
+
+    /// Neither display nor record the game, for the games between two computers.
+    pub fn headless(mut self) -> Self {
+        self.headless = true;
+        self
+    }
+
+    /// Choose who starts the games, the given game excepted, see [`common::FirstPlayer`].
+    pub fn with_first_player(mut self, p_first_player: common::FirstPlayer) -> Self {
+        self.first_player = Some(p_first_player);
+        self
+    }
+
+    /// Play a series of at most `p_games` games, won by the first player winning more than half of them.
+    pub fn with_best_of(mut self, p_games: u32) -> Self {
+        self.best_of = Some(p_games);
+        self
+    }
+
+    /// Play the game created with the misère rules, the player completing a line loses, a given game keeps its own
+    /// rules.
+    pub fn with_misere(mut self) -> Self {
+        self.misere = true;
+        self
+    }
+
+    /// Play the game created with the gravity, the players choose a column, a given game keeps its own rules.
+    pub fn with_gravity(mut self) -> Self {
+        self.gravity = true;
+        self
+    }
+}
+
 /// The structure saving all the elements necessary for the good functioning of the state machine
 pub struct StateMachine {
     /// The [Sender] to the [Receiver] of the state machine
     sender: Sender<MqMsg>,
-    /// The handler of the thread running the state machine
-    handler: thread::JoinHandle<()>,
+    /// The handler of the thread running the state machine, returning the outcome of the game
+    handler: thread::JoinHandle<game::GameOutcome>,
 }
 
 impl StateMachine {
     /// Create and launch the state machine, but not the game, see [`StateMachine::start_game`] to finish the game, see [`run`] to see the routine of the thread.
-    pub fn new_and_start() -> Self {
+    ///
+    /// The elements of the game missing from `p_setup` are asked to the players, see [`Setup`].
+    pub fn new_and_start(p_setup: Setup) -> Self {
         INFO!("[StateMachine] Event : Create the state machine");
 
         let (l_sender, l_receiver): (Sender<MqMsg>, Receiver<MqMsg>) = mpsc::channel();
@@ -50,19 +164,17 @@ impl StateMachine {
 
         Self {
             sender: l_sender,
-            handler: thread::spawn(move || {
-                run(&l_sender_copy, &l_receiver);
-            }),
+            handler: thread::spawn(move || run(&l_sender_copy, &l_receiver, p_setup)),
         }
     }
 
-    /// Send the signal to the state machine to start the game
+    /// Send the signal to the state machine to start the game, with the turn of the current player of the game
     pub fn start_game(&self) {
         INFO!("[StateMachine] Event : Start the game");
 
         self.sender
             .send(MqMsg {
-                event: Event::PlayerOneTurn,
+                event: Event::PositionChanged,
             })
             .expect("[StateMachine] - Fail to start the game");
     }
@@ -78,12 +190,16 @@ impl StateMachine {
     }
 
     /// Block the current thread while the the thread of the state machine is not finished, see [`StateMachine::finish_game`]
-    pub fn wait_end_game(self) {
-        self.handler
+    ///
+    /// Return the outcome of the last game, [`game::GameOutcome::InProgress`] if it has been quit before its end.
+    pub fn wait_end_game(self) -> game::GameOutcome {
+        let l_outcome = self
+            .handler
             .join()
             .expect("[StateMachine] Error when joining the thread");
 
         INFO!("[StateMachine] Game is finish");
+        l_outcome
     }
 }
 
@@ -93,6 +209,27 @@ impl StateMachine {
 //
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The action executed when a transition is made
+type Action = fn(&Sender<MqMsg>, &screen::Screen, &mut game::Game, &mut Series);
+
+/// The games played by the same players, and their score
+struct Series {
+    /// Who plays each player
+    players: Players,
+    /// If set, the games are neither displayed nor recorded
+    headless: bool,
+    /// The number of games of the series, if any, otherwise the players are asked to play again
+    best_of: Option<u32>,
+    /// Who starts the games
+    first_player: common::FirstPlayer,
+    /// The games won by player one and player two
+    wins: [u32; 2],
+    /// The drawn games
+    draws: u32,
+    /// If set, the players have joined the current game, see [`player::PlayerController::join_game`]
+    joined: bool,
+}
+
 #[derive(Debug)]
 struct MqMsg {
     event: Event,
@@ -108,6 +245,8 @@ enum Event {
     EndGame,
     PlayerOneTurn,
     PlayerTwoTurn,
+    PositionChanged,
+    NewGame,
     Quit,
 }
 
@@ -117,6 +256,7 @@ enum GameWrapper {
     PlayerTwoTurn(Game<PlayerTwoTurn>),
     TestGameStatus(Game<TestGameStatus>),
     TestPlayerTurn(Game<TestPlayerTurn>),
+    GameOver(Game<GameOver>),
     Quit(Game<Quit>),
 }
 
@@ -132,6 +272,9 @@ struct TestGameStatus {}
 #[derive(Debug, Copy, Clone, PartialEq)]
 struct TestPlayerTurn {}
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct GameOver {}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 struct Quit {}
 
@@ -160,6 +303,15 @@ impl From<&Game<PlayerTwoTurn>> for Game<TestGameStatus> {
     }
 }
 
+impl From<&Game<TestPlayerTurn>> for Game<TestGameStatus> {
+    fn from(_previous_state: &Game<TestPlayerTurn>) -> Game<TestGameStatus> {
+        DEBUG!("[StateMachine] - Transition : From TestPlayerTurn to TestGameStatus");
+        Game {
+            state: TestGameStatus {},
+        }
+    }
+}
+
 impl From<&Game<TestGameStatus>> for Game<TestPlayerTurn> {
     fn from(_previous_state: &Game<TestGameStatus>) -> Game<TestPlayerTurn> {
         DEBUG!("[StateMachine] - Transition : From TestGameStatus to TestPlayerTurn");
@@ -187,14 +339,58 @@ impl From<&Game<TestPlayerTurn>> for Game<PlayerTwoTurn> {
     }
 }
 
+impl From<&Game<PlayerOneTurn>> for Game<TestPlayerTurn> {
+    fn from(_previous_state: &Game<PlayerOneTurn>) -> Game<TestPlayerTurn> {
+        DEBUG!("[StateMachine] - Transition : From PlayerOneTurn to TestPlayerTurn");
+        Game {
+            state: TestPlayerTurn {},
+        }
+    }
+}
+
+impl From<&Game<PlayerTwoTurn>> for Game<TestPlayerTurn> {
+    fn from(_previous_state: &Game<PlayerTwoTurn>) -> Game<TestPlayerTurn> {
+        DEBUG!("[StateMachine] - Transition : From PlayerTwoTurn to TestPlayerTurn");
+        Game {
+            state: TestPlayerTurn {},
+        }
+    }
+}
+
+impl From<&Game<TestGameStatus>> for Game<GameOver> {
+    fn from(_previous_state: &Game<TestGameStatus>) -> Game<GameOver> {
+        DEBUG!("[StateMachine] - Transition : From TestGameStatus to GameOver");
+        Game { state: GameOver {} }
+    }
+}
+
+impl From<&Game<GameOver>> for Game<TestPlayerTurn> {
+    fn from(_previous_state: &Game<GameOver>) -> Game<TestPlayerTurn> {
+        DEBUG!("[StateMachine] - Transition : From GameOver to TestPlayerTurn");
+        Game {
+            state: TestPlayerTurn {},
+        }
+    }
+}
+
 //////////////////////////////////////////// Actions //////////////////////////////////////////////////////////////////
 
-fn action_none(_p_sender: &Sender<MqMsg>, _p_screen: &screen::Screen, _p_game: &mut game::Game) {
+fn action_none(
+    _p_sender: &Sender<MqMsg>,
+    _p_screen: &screen::Screen,
+    _p_game: &mut game::Game,
+    _p_series: &mut Series,
+) {
     INFO!("[StateMachine] - Action : None");
     // Nothing to do
 }
 
-fn action_quit(_p_sender: &Sender<MqMsg>, _p_screen: &screen::Screen, _p_game: &mut game::Game) {
+fn action_quit(
+    _p_sender: &Sender<MqMsg>,
+    _p_screen: &screen::Screen,
+    _p_game: &mut game::Game,
+    _p_series: &mut Series,
+) {
     INFO!("[StateMachine] - Action : Quit");
     // Nothing to do
 }
@@ -203,44 +399,83 @@ fn action_next_turn(
     _p_sender: &Sender<MqMsg>,
     _p_screen: &screen::Screen,
     _p_game: &mut game::Game,
+    _p_series: &mut Series,
 ) {
     INFO!("[StateMachine] - Action : Next Turn");
     _p_game.toggle_player();
     _p_screen.send_msg("Next Turn");
     _p_screen.send_game(_p_game);
 
-    match _p_game.current_player() {
-        common::Player::PlayerOne => {
-            _p_sender
-                .send(MqMsg {
-                    event: Event::PlayerOneTurn,
-                })
-                .expect("[StateMachine] - Error : Error occur when sending Event::PlayerOneTurn");
-        }
-        common::Player::PlayerTwo => {
+    send_player_turn(_p_sender, _p_game);
+}
+
+fn action_position_changed(
+    _p_sender: &Sender<MqMsg>,
+    _p_screen: &screen::Screen,
+    _p_game: &mut game::Game,
+    _p_series: &mut Series,
+) {
+    INFO!("[StateMachine] - Action : Position changed");
+    if !_p_series.joined {
+        _p_series.joined = join_game(_p_screen, _p_game, &mut _p_series.players);
+        if !_p_series.joined {
             _p_sender
-                .send(MqMsg {
-                    event: Event::PlayerTwoTurn,
-                })
-                .expect("[StateMachine] - Error : Error occur when sending Event::PlayerTwoTurn");
+                .send(MqMsg { event: Event::Quit })
+                .expect("[StateMachine] Error can not send the event Quit");
+            return;
         }
     }
+
+    if _p_game.is_over() {
+        // A finished position has been loaded, the game ends without another turn
+        _p_sender
+            .send(MqMsg {
+                event: Event::EndTurn,
+            })
+            .expect("[StateMachine] Error can not send the event EndTurn");
+    } else {
+        _p_screen.send_game(_p_game);
+        send_player_turn(_p_sender, _p_game);
+    }
 }
 
 fn action_end_turn(
     _p_sender: &Sender<MqMsg>,
     _p_screen: &screen::Screen,
     _p_game: &mut game::Game,
+    _p_series: &mut Series,
 ) {
     INFO!("[StateMachine] - Action : End Turn");
     if _p_game.is_over() {
         _p_screen.send_game(_p_game);
 
-        let winner = _p_game.current_player();
-        if winner == common::Player::PlayerOne {
-            _p_screen.send_msg("Player one WIN !");
-        } else {
-            _p_screen.send_msg("Player two WIN !");
+        match (_p_game.outcome(), _p_game.victory()) {
+            (game::GameOutcome::Won(l_winner), Some(l_victory)) => {
+                if _p_game.is_misere() {
+                    match l_victory.player() {
+                        common::Player::PlayerOne => {
+                            _p_screen.send_msg("Player one has completed a line...")
+                        }
+                        common::Player::PlayerTwo => {
+                            _p_screen.send_msg("Player two has completed a line...")
+                        }
+                    }
+                }
+                match l_winner {
+                    common::Player::PlayerOne => _p_screen.send_msg("Player one WIN !"),
+                    common::Player::PlayerTwo => _p_screen.send_msg("Player two WIN !"),
+                }
+                INFO!(
+                    "[StateMachine] {:?} won, {:?} completed the line(s) {:?}",
+                    l_winner,
+                    l_victory.player(),
+                    l_victory.lines()
+                );
+            }
+            _ => {
+                _p_screen.send_msg("It's a DRAW, the grid is full !");
+                INFO!("[StateMachine] The game ended with a draw");
+            }
         }
 
         _p_sender
@@ -257,48 +492,228 @@ fn action_end_turn(
     }
 }
 
+fn action_game_over(
+    _p_sender: &Sender<MqMsg>,
+    _p_screen: &screen::Screen,
+    _p_game: &mut game::Game,
+    _p_series: &mut Series,
+) {
+    INFO!("[StateMachine] - Action : Game over");
+    match _p_game.outcome() {
+        game::GameOutcome::Won(l_winner) => _p_series.wins[player_index(l_winner)] += 1,
+        _ => _p_series.draws += 1,
+    }
+    if !_p_series.headless {
+        record_game(_p_screen, _p_game, &_p_series.players);
+    }
+    _p_screen.send_msg(&format!(
+        "Score : player one {} - {} player two, {} draw(s)",
+        _p_series.wins[0], _p_series.wins[1], _p_series.draws
+    ));
+
+    let l_has_human = _p_series.players.iter().any(|l_player| l_player.is_human());
+    let l_new_game = match _p_series.best_of {
+        Some(_) if _p_series.is_decided() => {
+            match _p_series.wins[0].cmp(&_p_series.wins[1]) {
+                Ordering::Greater => _p_screen.send_msg("Player one WINS THE SERIES !"),
+                Ordering::Less => _p_screen.send_msg("Player two WINS THE SERIES !"),
+                Ordering::Equal => _p_screen.send_msg("The series is a DRAW !"),
+            }
+            false
+        }
+        Some(_) if l_has_human => {
+            _p_screen.send_msg("Press Enter for the next game, q to quit");
+            !game::read_keyboard().trim().eq_ignore_ascii_case("q")
+        }
+        Some(_) => true,
+        None if l_has_human => ask_rematch(_p_screen),
+        None => false,
+    };
+
+    let l_event = if l_new_game {
+        Event::NewGame
+    } else {
+        Event::Quit
+    };
+    _p_sender
+        .send(MqMsg { event: l_event })
+        .expect("[StateMachine] Error can not send the event of the end of the game");
+}
+
+fn action_new_game(
+    _p_sender: &Sender<MqMsg>,
+    _p_screen: &screen::Screen,
+    _p_game: &mut game::Game,
+    _p_series: &mut Series,
+) {
+    INFO!("[StateMachine] - Action : New game");
+    *_p_game = game::Game::new(_p_game.rows(), _p_game.columns(), _p_game.win_length())
+        .with_first_player(_p_series.first_player.choose(_p_series.games()))
+        .with_misere(_p_game.is_misere())
+        .with_gravity(_p_game.has_gravity());
+
+    _p_screen.send_game(_p_game);
+    send_first_player(_p_screen, _p_game, _p_series.games());
+    _p_series.joined = false;
+    _p_sender
+        .send(MqMsg {
+            event: Event::PositionChanged,
+        })
+        .expect("[StateMachine] Error can not send the event PositionChanged");
+}
+
 fn action_player_one(
     _p_sender: &Sender<MqMsg>,
     _p_screen: &screen::Screen,
     _p_game: &mut game::Game,
+    _p_series: &mut Series,
 ) {
     INFO!("[StateMachine] - Action : Player one is playing");
 
-    if game::player_turn(_p_screen, _p_game) {
-        _p_sender
-            .send(MqMsg {
-                event: Event::EndTurn,
-            })
-            .expect("[StateMachine] Error can not send event endTurn");
-    } else {
-        _p_sender
-            .send(MqMsg { event: Event::Quit })
-            .expect("[StateMachine] Error can not send event endTurn");
-    }
+    send_turn_status(
+        _p_sender,
+        play_turn(_p_screen, _p_game, &mut _p_series.players),
+    );
 }
 
 fn action_player_two(
     _p_sender: &Sender<MqMsg>,
     _p_screen: &screen::Screen,
     _p_game: &mut game::Game,
+    _p_series: &mut Series,
 ) {
     INFO!("[StateMachine] - Action : Player two is playing");
 
-    if game::player_turn(_p_screen, _p_game) {
-        _p_sender
-            .send(MqMsg {
-                event: Event::EndTurn,
-            })
-            .expect("[StateMachine] Error can not send event endTurn");
-    } else {
-        _p_sender
-            .send(MqMsg { event: Event::Quit })
-            .expect("[StateMachine] Error can not send event endTurn");
+    send_turn_status(
+        _p_sender,
+        play_turn(_p_screen, _p_game, &mut _p_series.players),
+    );
+}
+
+/// Plays the turn of the current player of the game with its controller, and notify the other one.
+///
+/// When a human undoes a move played by the computer, the move of the human before it is undone as well, so that the
+/// human can play again instead of the computer.
+fn play_turn(
+    p_screen: &screen::Screen,
+    p_game: &mut game::Game,
+    p_players: &mut Players,
+) -> game::TurnStatus {
+    let l_index = player_index(p_game.current_player());
+    let l_moves_before = p_game.moves().len();
+    let l_status = p_players[l_index].play_turn(p_screen, p_game);
+
+    if l_status == game::TurnStatus::Changed
+        && p_game.moves().len() < l_moves_before
+        && !p_players[player_index(p_game.current_player())].may_undo()
+    {
+        p_game.undo();
+    }
+
+    p_players[1 - l_index].notify(p_game, l_status);
+    l_status
+}
+
+/// Tell the players that the game starts, return `false` if one of them can not play it, see
+/// [`player::PlayerController::join_game`].
+fn join_game(p_screen: &screen::Screen, p_game: &game::Game, p_players: &mut Players) -> bool {
+    p_players
+        .iter_mut()
+        .all(|l_player| l_player.join_game(p_screen, p_game))
+}
+
+/// Ask the players if they want to play again, return `true` if so.
+fn ask_rematch(p_screen: &screen::Screen) -> bool {
+    p_screen.send_msg("\x1B[34mPlay again ? y for yes, n for no (empty for no):\x1B[0m ");
+
+    loop {
+        match game::read_keyboard().trim().to_lowercase().as_str() {
+            "y" => return true,
+            "" | "n" | "q" => return false,
+            _ => p_screen.send_msg("\x1B[41mBad entry, please enter y or n :\x1B[0m  "),
+        }
+    }
+}
+
+/// Record the game in the directory [`record::RECORDS_DIRECTORY`].
+fn record_game(p_screen: &screen::Screen, p_game: &game::Game, p_players: &Players) {
+    let l_record =
+        record::GameRecord::from_game(p_game, &p_players[0].name(), &p_players[1].name());
+    match l_record.save(record::RECORDS_DIRECTORY) {
+        Ok(l_path) => p_screen.send_msg(&format!("Game recorded in {}", l_path)),
+        Err(l_error) => WARNING!("[StateMachine] The game can not be recorded, {}", l_error),
+    }
+}
+
+/// Send the player starting the game of the given number, counted from 0
+fn send_first_player(p_screen: &screen::Screen, p_game: &game::Game, p_game_number: u32) {
+    match p_game.current_player() {
+        common::Player::PlayerOne => {
+            p_screen.send_msg(&format!("Game {} : player one starts", p_game_number + 1))
+        }
+        common::Player::PlayerTwo => {
+            p_screen.send_msg(&format!("Game {} : player two starts", p_game_number + 1))
+        }
+    }
+}
+
+/// Return the index of the player in [`Players`]
+fn player_index(p_player: common::Player) -> usize {
+    match p_player {
+        common::Player::PlayerOne => 0,
+        common::Player::PlayerTwo => 1,
+    }
+}
+
+/// Send the event of the turn of the current player of the game
+fn send_player_turn(p_sender: &Sender<MqMsg>, p_game: &game::Game) {
+    match p_game.current_player() {
+        common::Player::PlayerOne => {
+            p_sender
+                .send(MqMsg {
+                    event: Event::PlayerOneTurn,
+                })
+                .expect("[StateMachine] - Error : Error occur when sending Event::PlayerOneTurn");
+        }
+        common::Player::PlayerTwo => {
+            p_sender
+                .send(MqMsg {
+                    event: Event::PlayerTwoTurn,
+                })
+                .expect("[StateMachine] - Error : Error occur when sending Event::PlayerTwoTurn");
+        }
     }
 }
 
+/// Send the event matching what the player did during its turn, see [`game::TurnStatus`]
+fn send_turn_status(p_sender: &Sender<MqMsg>, p_status: game::TurnStatus) {
+    let l_event = match p_status {
+        game::TurnStatus::Played => Event::EndTurn,
+        game::TurnStatus::Changed => Event::PositionChanged,
+        game::TurnStatus::Quit => Event::Quit,
+    };
+
+    p_sender
+        .send(MqMsg { event: l_event })
+        .expect("[StateMachine] Error can not send the event of the end of the turn");
+}
+
 /////////////////////////////////////////// Functions /////////////////////////////////////////////////////////////////
 
+impl Series {
+    /// Return the number of games finished
+    fn games(&self) -> u32 {
+        self.wins[0] + self.wins[1] + self.draws
+    }
+
+    /// Return `true` if the series is over: every game has been played, or a player has won more than half of them
+    fn is_decided(&self) -> bool {
+        self.best_of.is_some_and(|l_best_of| {
+            self.games() >= l_best_of || self.wins.iter().any(|l_wins| l_wins * 2 > l_best_of)
+        })
+    }
+}
+
 impl Game<TestPlayerTurn> {
     pub fn new() -> Self {
         Game {
@@ -323,16 +738,10 @@ impl GameWrapper {
     }
 
     pub fn is_quit(&self) -> bool {
-        match self {
-            GameWrapper::Quit(_) => true,
-            _ => false,
-        }
+        matches!(self, GameWrapper::Quit(_))
     }
 
-    pub fn step(
-        &self,
-        event: &Event,
-    ) -> Result<(Self, fn(&Sender<MqMsg>, &screen::Screen, &mut game::Game)), ()> {
+    pub fn step(&self, event: &Event) -> Result<(Self, Action), ()> {
         match (self, event) {
             (GameWrapper::PlayerOneTurn(_previous_state), Event::EndTurn) => Ok((
                 GameWrapper::TestGameStatus(_previous_state.into()),
@@ -342,13 +751,34 @@ impl GameWrapper {
                 GameWrapper::TestGameStatus(_previous_state.into()),
                 action_end_turn,
             )),
-            (GameWrapper::TestGameStatus(_previous_state), Event::EndGame) => {
-                Ok((GameWrapper::quit(), action_quit))
-            }
+            (GameWrapper::PlayerOneTurn(_previous_state), Event::PositionChanged) => Ok((
+                GameWrapper::TestPlayerTurn(_previous_state.into()),
+                action_position_changed,
+            )),
+            (GameWrapper::PlayerTwoTurn(_previous_state), Event::PositionChanged) => Ok((
+                GameWrapper::TestPlayerTurn(_previous_state.into()),
+                action_position_changed,
+            )),
+            (GameWrapper::TestGameStatus(_previous_state), Event::EndGame) => Ok((
+                GameWrapper::GameOver(_previous_state.into()),
+                action_game_over,
+            )),
+            (GameWrapper::GameOver(_previous_state), Event::NewGame) => Ok((
+                GameWrapper::TestPlayerTurn(_previous_state.into()),
+                action_new_game,
+            )),
             (GameWrapper::TestGameStatus(_previous_state), Event::NextTurn) => Ok((
                 GameWrapper::TestPlayerTurn(_previous_state.into()),
                 action_next_turn,
             )),
+            (GameWrapper::TestPlayerTurn(_previous_state), Event::PositionChanged) => Ok((
+                GameWrapper::TestPlayerTurn(*_previous_state),
+                action_position_changed,
+            )),
+            (GameWrapper::TestPlayerTurn(_previous_state), Event::EndTurn) => Ok((
+                GameWrapper::TestGameStatus(_previous_state.into()),
+                action_end_turn,
+            )),
             (GameWrapper::TestPlayerTurn(_previous_state), Event::PlayerOneTurn) => Ok((
                 GameWrapper::PlayerOneTurn(_previous_state.into()),
                 action_player_one,
@@ -366,22 +796,70 @@ impl GameWrapper {
     }
 }
 
-fn run(p_sender: &Sender<MqMsg>, p_receiver: &Receiver<MqMsg>) {
+fn run(
+    p_sender: &Sender<MqMsg>,
+    p_receiver: &Receiver<MqMsg>,
+    p_setup: Setup,
+) -> game::GameOutcome {
     INFO!("[StateMachine] Start the state machine");
 
     let mut l_current_state: GameWrapper = GameWrapper::new();
-    let l_screen = screen::Screen::new_and_start();
-    let mut l_game: game::Game = game::create_game(&l_screen);
+    let l_screen = if p_setup.headless {
+        screen::Screen::new_muted()
+    } else {
+        screen::Screen::new_and_start()
+    };
+    // The given game is played as it is, the first player is chosen from the next game
+    let l_given_game = p_setup.game.is_some();
+    let mut l_game = match p_setup.game {
+        Some(l_game) => l_game,
+        None => game::create_game(&l_screen, p_setup.size, p_setup.win_length)
+            .with_misere(p_setup.misere)
+            .with_gravity(p_setup.gravity),
+    };
+    let [l_player_one, l_player_two] = p_setup.players;
+    let l_players: Players = [
+        l_player_one.unwrap_or_else(|| game::ask_player(&l_screen, common::Player::PlayerOne)),
+        l_player_two.unwrap_or_else(|| game::ask_player(&l_screen, common::Player::PlayerTwo)),
+    ];
+    let mut l_first_player = match p_setup.first_player {
+        Some(l_first_player) => l_first_player,
+        None if l_given_game => common::FirstPlayer::Alternate,
+        None => game::ask_first_player(&l_screen),
+    };
+    if l_first_player == common::FirstPlayer::Random
+        && l_players.iter().any(|l_player| l_player.is_remote())
+    {
+        l_screen.send_msg(
+            "\x1B[41mThe other program can not share the coin toss, the players start in turn\x1B[0m",
+        );
+        l_first_player = common::FirstPlayer::Alternate;
+    }
+    if !l_given_game {
+        l_game = l_game.with_first_player(l_first_player.choose(0));
+    }
+    let mut l_series = Series {
+        players: l_players,
+        headless: p_setup.headless,
+        best_of: p_setup.best_of,
+        first_player: l_first_player,
+        wins: [0, 0],
+        draws: 0,
+        joined: false,
+    };
 
     l_screen.send_game(&l_game);
-    while l_current_state.is_quit() != true {
+    if l_game.moves().is_empty() {
+        send_first_player(&l_screen, &l_game, 0);
+    }
+    while !l_current_state.is_quit() {
         let l_msg: MqMsg = p_receiver
             .recv()
             .expect("[StateMachine] Error when receiving the message in the channel");
 
         l_current_state = match l_current_state.step(&l_msg.event) {
             Ok((l_new_state, l_callback)) => {
-                l_callback(p_sender, &l_screen, &mut l_game);
+                l_callback(p_sender, &l_screen, &mut l_game, &mut l_series);
                 l_new_state
             }
             Err(_) => {
@@ -389,5 +867,123 @@ fn run(p_sender: &Sender<MqMsg>, p_receiver: &Receiver<MqMsg>) {
             }
         };
     }
+
+    // The finished games are recorded at their end, see [`action_game_over`]
+    if !p_setup.headless && !l_game.is_over() {
+        record_game(&l_screen, &l_game, &l_series.players);
+    }
+
     l_screen.stop_and_free();
+    l_game.outcome()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Play headless the game between two scripts filling the given cells, return its outcome
+    fn play_scripts(
+        p_game: game::Game,
+        p_moves_one: Vec<usize>,
+        p_moves_two: Vec<usize>,
+    ) -> game::GameOutcome {
+        play_setup(Setup::new().with_game(p_game), p_moves_one, p_moves_two)
+    }
+
+    /// Play headless the games of the setup between two scripts filling the given cells, return the outcome of the
+    /// last game
+    fn play_setup(
+        p_setup: Setup,
+        p_moves_one: Vec<usize>,
+        p_moves_two: Vec<usize>,
+    ) -> game::GameOutcome {
+        let l_setup = p_setup
+            .with_player(
+                common::Player::PlayerOne,
+                Box::new(player::Scripted::new(p_moves_one)),
+            )
+            .with_player(
+                common::Player::PlayerTwo,
+                Box::new(player::Scripted::new(p_moves_two)),
+            )
+            .headless();
+
+        let l_state_machine = StateMachine::new_and_start(l_setup);
+        l_state_machine.start_game();
+        l_state_machine.wait_end_game()
+    }
+
+    #[test]
+    fn scripted_win() {
+        assert_eq!(
+            play_scripts(game::Game::new(3, 3, 3), vec![0, 1, 2], vec![3, 4]),
+            game::GameOutcome::Won(common::Player::PlayerOne)
+        );
+    }
+
+    #[test]
+    fn scripted_draw() {
+        assert_eq!(
+            play_scripts(
+                game::Game::new(3, 3, 3),
+                vec![0, 2, 3, 7, 5],
+                vec![1, 4, 6, 8]
+            ),
+            game::GameOutcome::Draw
+        );
+    }
+
+    #[test]
+    fn script_over_quits_the_game() {
+        assert_eq!(
+            play_scripts(game::Game::new(3, 3, 3), vec![0, 1], vec![3]),
+            game::GameOutcome::InProgress
+        );
+    }
+
+    #[test]
+    fn series_stops_at_two_wins() {
+        // Player one wins the first two games, the third one would end at once, the scripts being over
+        let l_setup = Setup::new()
+            .with_game(game::Game::new(3, 3, 3))
+            .with_best_of(3);
+
+        assert_eq!(
+            play_setup(l_setup, vec![0, 1, 2, 0, 1, 2], vec![3, 4, 6, 7, 5]),
+            game::GameOutcome::Won(common::Player::PlayerOne)
+        );
+    }
+
+    #[test]
+    fn draws_are_not_wins() {
+        // A draw, then a win of each player, the players starting in turn
+        let l_setup = Setup::new()
+            .with_game(game::Game::new(3, 3, 3))
+            .with_best_of(3);
+
+        assert_eq!(
+            play_setup(
+                l_setup,
+                vec![0, 2, 3, 7, 5, 0, 1, 2, 0, 1, 8],
+                vec![1, 4, 6, 8, 6, 7, 5, 3, 4, 5]
+            ),
+            game::GameOutcome::Won(common::Player::PlayerTwo)
+        );
+    }
+
+    #[test]
+    fn finished_position_ends_the_game() {
+        let l_game = game::Game::from_notation("3x3 3 XXX/OO./... O").unwrap();
+
+        assert_eq!(
+            play_scripts(l_game, vec![], vec![5]),
+            game::GameOutcome::Won(common::Player::PlayerOne)
+        );
+    }
+
+    #[test]
+    fn finished_position_ends_the_turn() {
+        let (l_state, _) = GameWrapper::new().step(&Event::EndTurn).unwrap();
+        assert!(matches!(l_state, GameWrapper::TestGameStatus(_)));
+    }
 }
@@ -9,6 +9,10 @@
 //!
 //! The format of the messages will be : `file:line - LEVEL >> message`
 //!
+//! The messages below a level can be hidden while the program runs, see [`set_level`]: for example at the level `INFO`
+//! only the [`INFO`], [`WARNING`] and [`ERROR`] messages are displayed. In _release_ mode, no message is displayed
+//! whatever the level.
+//!
 //! # Example
 //!
 //! ```rust
@@ -42,16 +46,67 @@
 //!
 //! Pierre-Louis GAUTIER
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The levels of the messages, from the lowest to the highest, see [`set_level`]
+pub const LEVELS: [&str; 6] = ["TRACE", "DEBUG", "INFO", "WARNING", "ERROR", "NONE"];
+
+/// Hide the messages below the given level, one of [`LEVELS`], `NONE` hiding every message.
+///
+/// Return an error if the level does not exist.
+pub fn set_level(p_level: &str) -> Result<(), String> {
+    let l_level = p_level.trim().to_uppercase();
+
+    match LEVELS.iter().position(|l_name| *l_name == l_level) {
+        Some(l_index) => {
+            LEVEL.store(l_index, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!(
+            "`{}` is not a level, expected one of {}",
+            p_level.trim(),
+            LEVELS.join(", ").to_lowercase()
+        )),
+    }
+}
+
+/// Return `true` if the messages of the given level are displayed, see [`set_level`]
+pub fn is_enabled(p_level: &str) -> bool {
+    LEVELS
+        .iter()
+        .position(|l_name| *l_name == p_level)
+        .is_some_and(|l_index| l_index >= LEVEL.load(Ordering::Relaxed))
+}
+
+/// The index in [`LEVELS`] of the lowest level displayed
+static LEVEL: AtomicUsize = AtomicUsize::new(0);
+
 #[macro_export]
 macro_rules! TRACE {
-    (type=>$tp:expr, $($arg:tt)+) => {{ TRACE!("{} >> {}", $tp, format_args!($($arg)+)) }};
-    (type=>$tp:expr) => {{ TRACE!("{}", $tp) }};
-    ($($arg:tt)+) => {{
+    (type=>$tp:expr, $($arg:tt)+) => {{
+        if cfg!(debug_assertions) && $crate::tools::is_enabled($tp) {
+            TRACE!(@print "{} >> {}", $tp, format_args!($($arg)+))
+        }
+    }};
+    (type=>$tp:expr) => {{
+        if cfg!(debug_assertions) && $crate::tools::is_enabled($tp) {
+            TRACE!(@print "{}", $tp)
+        }
+    }};
+    (@print $($arg:tt)+) => {{
         #[cfg(debug_assertions)]
         println!("{}", format_args!("{}:{} - {}", file!(), line!(), format_args!($($arg)+)));
         #[cfg(not(debug_assertions))]
-        {} // TODO write in FILE
-}};
+        if false {
+            // TODO write in FILE, meanwhile the arguments are still considered as used
+            let _ = format_args!($($arg)+);
+        }
+    }};
+    ($($arg:tt)+) => {{
+        if cfg!(debug_assertions) && $crate::tools::is_enabled("TRACE") {
+            TRACE!(@print $($arg)+)
+        }
+    }};
 }
 
 #[macro_export]
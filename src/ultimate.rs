@@ -0,0 +1,337 @@
+//! Module in charge of the ultimate tic-tac-toe, a tic-tac-toe of tic-tac-toe games.
+//!
+//! The grid is made of nine 3x3 boards, each one being a [`game::Game`], placed on a 3x3 meta-board, also a
+//! [`game::Game`]. The boards and their cells are numbered from 0 to 8, row by row from the top.
+//!
+//! The rules are:
+//! - each move is played in a cell of one of the boards, the player winning a board fills the matching cell of the
+//!   meta-board
+//! - the cell played sends the opponent to the board of the same number: a move in the cell 2 of any board forces the
+//!   opponent to play in the board 2, unless this board is already won or full, the opponent then plays in any board
+//! - the first player aligning three boards on the meta-board wins the game, the game is drawn once every board is
+//!   over without such a line
+//!
+//! A move is entered as `<board> <cell>`, the board may be omitted when the opponent has sent the player to a board,
+//! see [`player_turn`]. The game has its own loop, see [`run`], and is displayed by the [`screen::Screen`].
+//!
+//! # Example
+//!
+//! ```rust
+//! mod ultimate;
+//!
+//! let mut game = ultimate::UltimateGame::new();
+//! game.play(4, 0).unwrap(); // Player one sends player two to the board 0
+//! game.play(0, 8).unwrap(); // Player two sends player one to the board 8
+//! assert_eq!(game.next_board(), Some(8));
+//! ```
+//!
+//! # Author
+//! Pierre-Louis GAUTIER
+
+use crate::{common, game, screen, DEBUG, INFO, TRACE};
+use std::fmt;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Public
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The number of boards, and of cells in each board
+pub const BOARDS: usize = 9;
+
+/// The reasons why a move can be refused, see [`UltimateGame::play`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MoveError {
+    /// The board or the cell is not a number from 0 to 8
+    OutOfBounds,
+    /// The player has been sent to another board, the given one
+    WrongBoard(usize),
+    /// The board is already won or full
+    BoardOver,
+    /// The move is refused by the board, see [`game::MoveError`]
+    Cell(game::MoveError),
+}
+
+/// Implementation of the [`fmt::Display`] trait for a [`MoveError`], to explain the error to the players
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::OutOfBounds => write!(f, "the board and the cell are numbers from 0 to 8"),
+            MoveError::WrongBoard(l_board) => write!(f, "you must play in the board {}", l_board),
+            MoveError::BoardOver => write!(f, "the board is already over"),
+            MoveError::Cell(l_error) => l_error.fmt(f),
+        }
+    }
+}
+
+/// The structure saving all the elements of an ultimate tic-tac-toe game, see the [module](self).
+#[derive(Debug, Clone)]
+pub struct UltimateGame {
+    /// The nine boards, row by row from the top
+    boards: Vec<game::Game>,
+    /// The meta-board, whose cells are filled by the winners of the boards
+    meta: game::Game,
+    /// The current player playing
+    current_player: common::Player,
+    /// The moves played, in order: the board and the cell
+    history: Vec<(usize, usize)>,
+}
+
+impl UltimateGame {
+    /// Create a new game, player one plays first in any board.
+    pub fn new() -> Self {
+        UltimateGame {
+            boards: vec![game::Game::new(3, 3, 3); BOARDS],
+            meta: game::Game::new(3, 3, 3),
+            current_player: common::Player::PlayerOne,
+            history: Vec::new(),
+        }
+    }
+
+    /// Return the board of the given number.
+    pub fn board(&self, p_board: usize) -> &game::Game {
+        &self.boards[p_board]
+    }
+
+    /// Return the current player, see [`common::Player`]
+    pub fn current_player(&self) -> common::Player {
+        self.current_player
+    }
+
+    /// Return the board where the current player has to play, `None` if the player can play in any board.
+    pub fn next_board(&self) -> Option<usize> {
+        self.history
+            .last()
+            .map(|(_, l_cell)| *l_cell)
+            .filter(|l_board| !self.boards[*l_board].is_over())
+    }
+
+    /// Test if the current player can play in the given board.
+    pub fn is_playable(&self, p_board: usize) -> bool {
+        !self.is_over()
+            && !self.boards[p_board].is_over()
+            && self.next_board().is_none_or(|l_board| l_board == p_board)
+    }
+
+    /// Fill the cell of the board with the current player, then give the turn to the other player.
+    ///
+    /// If the board is won by the move, the matching cell of the meta-board is filled by the player.
+    /// Return the [`MoveError`] explaining why the move is refused, if so.
+    pub fn play(&mut self, p_board: usize, p_cell: usize) -> Result<(), MoveError> {
+        if p_board >= BOARDS || p_cell >= BOARDS {
+            return Err(MoveError::OutOfBounds);
+        } else if self.is_over() {
+            return Err(MoveError::Cell(game::MoveError::GameAlreadyOver));
+        } else if self.boards[p_board].is_over() {
+            return Err(MoveError::BoardOver);
+        }
+        if let Some(l_board) = self.next_board().filter(|l_board| *l_board != p_board) {
+            return Err(MoveError::WrongBoard(l_board));
+        }
+
+        let l_player = self.current_player;
+        fill(&mut self.boards[p_board], p_cell, l_player).map_err(MoveError::Cell)?;
+        if self.boards[p_board].outcome() == game::GameOutcome::Won(l_player) {
+            DEBUG!("[Ultimate] Board {} won by {:?}", p_board, l_player);
+            fill(&mut self.meta, p_board, l_player).map_err(MoveError::Cell)?;
+        }
+
+        self.history.push((p_board, p_cell));
+        self.current_player = l_player.opponent();
+        Ok(())
+    }
+
+    /// Cancel the last move, the player who made it becomes the current player.
+    /// Return `false` if there is no move to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((l_board, _)) => {
+                // The last cell filled in the meta-board is the one of the last board won
+                if self.boards[l_board].victory().is_some() {
+                    self.meta.undo();
+                }
+                self.boards[l_board].undo();
+                self.current_player = self.current_player.opponent();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return the [`game::GameOutcome`] of the game in its current state.
+    ///
+    /// The game is won by the player aligning three boards on the meta-board, and drawn when every board is over.
+    pub fn outcome(&self) -> game::GameOutcome {
+        match self.meta.outcome() {
+            game::GameOutcome::Won(l_winner) => game::GameOutcome::Won(l_winner),
+            _ if self.boards.iter().all(|l_board| l_board.is_over()) => game::GameOutcome::Draw,
+            _ => game::GameOutcome::InProgress,
+        }
+    }
+
+    /// Test if the game is finished, by a win or a draw.
+    pub fn is_over(&self) -> bool {
+        self.outcome() != game::GameOutcome::InProgress
+    }
+}
+
+/// Play an ultimate tic-tac-toe game between two players at the keyboard, displayed on its own [`screen::Screen`].
+///
+/// Unlike the [state machine](crate::state_machine) of the tic-tac-toe, there is no computer player, the game is not
+/// recorded and no rematch is offered.
+pub fn run() {
+    INFO!("[Ultimate] Start the game");
+
+    let l_screen = screen::Screen::new_and_start();
+    let mut l_game = UltimateGame::new();
+
+    while !l_game.is_over() {
+        l_screen.send_ultimate_game(&l_game);
+        if player_turn(&l_screen, &mut l_game) == game::TurnStatus::Quit {
+            l_screen.send_msg("The game has been quit");
+            l_screen.stop_and_free();
+            return;
+        }
+    }
+
+    l_screen.send_ultimate_game(&l_game);
+    match l_game.outcome() {
+        game::GameOutcome::Won(common::Player::PlayerOne) => {
+            l_screen.send_msg("\x1B[32mPlayer one wins the game !\x1B[0m")
+        }
+        game::GameOutcome::Won(common::Player::PlayerTwo) => {
+            l_screen.send_msg("\x1B[31mPlayer two wins the game !\x1B[0m")
+        }
+        _ => l_screen.send_msg("It is a draw"),
+    }
+
+    INFO!("[Ultimate] Game over : {:?}", l_game.outcome());
+    l_screen.stop_and_free();
+}
+
+/// Plays the current player's turn of the [`UltimateGame`].
+///
+/// The player enters `<board> <cell>`, or only `<cell>` when sent to a board, `u` to undo the last move or `q` to quit
+/// the game. As long as a valid entry has not been made, the player will be asked again.
+pub fn player_turn(p_screen: &screen::Screen, p_game: &mut UltimateGame) -> game::TurnStatus {
+    let l_board = match p_game.next_board() {
+        Some(l_board) => format!("the cell of the board {}", l_board),
+        None => String::from("the board and the cell, <board> <cell>"),
+    };
+    game::announce_turn(p_screen, p_game.current_player(), &l_board, "u to undo");
+
+    game::read_turn(p_screen, p_game, UltimateGame::undo, |l_game, l_entry| {
+        parse_move(l_entry, l_game.next_board())
+            .map(|(l_board, l_cell)| l_game.play(l_board, l_cell))
+    })
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//                                              Private
+//
+///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Fill the cell of the given number of a 3x3 board with the player, whoever played the last move of this board.
+fn fill(
+    p_board: &mut game::Game,
+    p_cell: usize,
+    p_player: common::Player,
+) -> Result<(), game::MoveError> {
+    if p_board.current_player() != p_player {
+        p_board.toggle_player();
+    }
+
+    p_board.set_cell(p_cell / 3, p_cell % 3, p_player)
+}
+
+/// Read a move `<board> <cell>`, or `<cell>` if the player is sent to the board `p_next_board`.
+fn parse_move(p_entry: &str, p_next_board: Option<usize>) -> Option<(usize, usize)> {
+    let l_numbers: Vec<usize> = p_entry
+        .split_whitespace()
+        .map(|l_number| l_number.parse::<usize>().ok())
+        .collect::<Option<Vec<usize>>>()?;
+
+    match (l_numbers.as_slice(), p_next_board) {
+        ([l_board, l_cell], _) => Some((*l_board, *l_cell)),
+        ([l_cell], Some(l_board)) => Some((l_board, *l_cell)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return a game after the given moves `(board, cell)`, played in turn from player one
+    fn game_after(p_moves: &[(usize, usize)]) -> UltimateGame {
+        let mut l_game = UltimateGame::new();
+        for (l_board, l_cell) in p_moves {
+            l_game.play(*l_board, *l_cell).unwrap();
+        }
+        l_game
+    }
+
+    /// The moves of a game where player two wins the board 0 with its cells 3, 4 and 5, sending player one to the board 5
+    const BOARD_WON: [(usize, usize); 6] = [(0, 0), (0, 3), (3, 0), (0, 4), (4, 0), (0, 5)];
+
+    #[test]
+    fn move_sends_to_board() {
+        let mut l_game = game_after(&[(4, 0)]);
+
+        assert_eq!(l_game.next_board(), Some(0));
+        assert!(l_game.is_playable(0) && !l_game.is_playable(1));
+        assert_eq!(l_game.play(1, 0), Err(MoveError::WrongBoard(0)));
+        assert_eq!(l_game.play(0, 9), Err(MoveError::OutOfBounds));
+        assert_eq!(l_game.play(0, 2), Ok(()));
+        assert_eq!(l_game.next_board(), Some(2));
+    }
+
+    #[test]
+    fn free_choice_once_board_decided() {
+        let mut l_game = game_after(&BOARD_WON);
+        assert_eq!(
+            l_game.board(0).outcome(),
+            game::GameOutcome::Won(common::Player::PlayerTwo)
+        );
+
+        l_game.play(5, 0).unwrap();
+        assert_eq!(l_game.next_board(), None);
+        assert!(!l_game.is_playable(0) && l_game.is_playable(1));
+        assert_eq!(l_game.play(0, 8), Err(MoveError::BoardOver));
+        assert_eq!(l_game.play(7, 7), Ok(()));
+    }
+
+    #[test]
+    fn undo_move_winning_board() {
+        let mut l_game = game_after(&BOARD_WON);
+        assert_eq!(l_game.meta.moves().len(), 1);
+
+        assert!(l_game.undo());
+        assert!(l_game.meta.moves().is_empty());
+        assert_eq!(l_game.board(0).outcome(), game::GameOutcome::InProgress);
+        assert_eq!(l_game.current_player(), common::Player::PlayerTwo);
+        assert_eq!(l_game.next_board(), Some(0));
+
+        assert!(l_game.undo());
+        assert_eq!(l_game.next_board(), Some(4));
+    }
+
+    #[test]
+    fn meta_board_draw() {
+        let l_drawn = game::Game::from_notation("3x3 3 XOX/XOO/OXX O").unwrap();
+        let mut l_game = UltimateGame::new();
+        l_game.boards = vec![l_drawn; BOARDS];
+        l_game.boards[0] = game::Game::from_notation("3x3 3 XXX/OO./... O").unwrap();
+        l_game.boards[8] = game::Game::from_notation("3x3 3 OOO/XX./X.. X").unwrap();
+        fill(&mut l_game.meta, 0, common::Player::PlayerOne).unwrap();
+        fill(&mut l_game.meta, 8, common::Player::PlayerTwo).unwrap();
+
+        assert_eq!(l_game.outcome(), game::GameOutcome::Draw);
+        assert!(l_game.is_over());
+
+        l_game.boards[4] = game::Game::new(3, 3, 3);
+        assert_eq!(l_game.outcome(), game::GameOutcome::InProgress);
+    }
+}